@@ -0,0 +1,65 @@
+//! Parsing benchmarks, run with `cargo bench --bench frame`. They're timed with `Instant` so they build without
+//! pulling in a benchmarking framework.
+
+use std::hint::black_box;
+use std::io::Cursor;
+use std::time::Instant;
+
+use async_redis::Frame;
+use bytes::Bytes;
+
+const MB: usize = 1 << 20;
+
+/// Calls `f` a few times to warm up, then prints the mean time per call over `iterations`.
+fn bench<T>(name: &str, iterations: u32, mut f: impl FnMut() -> T) {
+    for _ in 0..3 {
+        black_box(f());
+    }
+    let start = Instant::now();
+    for _ in 0..iterations {
+        black_box(f());
+    }
+    println!("{name:<48} {:>12.3?}", start.elapsed() / iterations);
+}
+
+/// How `read_line` looked for the line end before it used `memchr`, kept as the baseline.
+fn find_line_end_windows(buf: &[u8]) -> Option<usize> {
+    buf.windows(2).position(|window| window == b"\r\n")
+}
+
+/// How `read_line` looks for it now.
+fn find_line_end_memchr(buf: &[u8]) -> Option<usize> {
+    let mut from = 0;
+    while let Some(i) = memchr::memchr(b'\r', &buf[from..]).map(|i| from + i) {
+        if buf.get(i + 1) == Some(&b'\n') {
+            return Some(i);
+        }
+        from = i + 1;
+    }
+    None
+}
+
+fn main() {
+    // only the header of a bulk reply is a line, the payload is skipped over by its length.
+    let mut bulk = format!("${MB}\r\n").into_bytes();
+    bulk.resize(bulk.len() + MB, b'x');
+    bulk.extend(b"\r\n");
+    bench("parse a 1 MB bulk reply", 100, || Frame::parse(&mut Cursor::new(&bulk[..])).unwrap());
+
+    // a simple string is one line from end to end, the worst case for the line search.
+    let mut simple = vec![b'+'];
+    simple.resize(MB, b'x');
+    simple.extend(b"\r\n");
+    bench("parse a 1 MB simple string", 100, || Frame::parse(&mut Cursor::new(&simple[..])).unwrap());
+    bench("find the end of a 1 MB line, windows (before)", 100, || find_line_end_windows(&simple));
+    bench("find the end of a 1 MB line, memchr (after)", 100, || find_line_end_memchr(&simple));
+
+    // the reply to a `GET` of a 10 MB value, `parse` copies the payload out while `parse_bytes`, which the
+    // connection reads with, hands out a slice of the buffer.
+    let mut get = format!("${}\r\n", 10 * MB).into_bytes();
+    get.resize(get.len() + 10 * MB, b'x');
+    get.extend(b"\r\n");
+    let get = Bytes::from(get);
+    bench("parse a 10 MB GET reply, copied", 20, || Frame::parse(&mut Cursor::new(&get[..])).unwrap());
+    bench("parse a 10 MB GET reply, zero copy", 20, || Frame::parse_bytes(&mut Cursor::new(get.clone())).unwrap());
+}
@@ -0,0 +1,84 @@
+//! Pipeline write benchmarks, run with `cargo bench --bench pipeline`. They're timed with `Instant` so they build
+//! without pulling in a benchmarking framework.
+
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use async_redis::{Connection, Frame, Pipeline};
+use bytes::{Bytes, BytesMut};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const COMMANDS: usize = 10_000;
+const VALUE: usize = 4 * 1024;
+const ITERATIONS: u32 = 10;
+
+/// Reads one pipeline's worth of requests at a time and answers each command with `+OK`.
+async fn server(request_len: usize) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            tokio::spawn(async move {
+                let mut request = vec![0; request_len];
+                let replies = b"+OK\r\n".repeat(COMMANDS);
+                while socket.read_exact(&mut request).await.is_ok() {
+                    socket.write_all(&replies).await.unwrap();
+                }
+            });
+        }
+    });
+
+    addr
+}
+
+fn report(name: &str, elapsed: Duration) {
+    println!("{name:<56} {:>12.3?}", elapsed / ITERATIONS);
+}
+
+fn main() {
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    runtime.block_on(async {
+        let value = vec![b'x'; VALUE];
+        let set = |value: &[u8]| {
+            let args = [b"SET" as &[u8], b"key", value];
+            Frame::Array(args.iter().map(|arg| Frame::Bulk(Bytes::copy_from_slice(arg))).collect())
+        };
+        let frames: Vec<_> = (0..COMMANDS).map(|_| set(&value)).collect();
+        let mut request = BytesMut::new();
+        frames.iter().for_each(|frame| frame.serialize(&mut request));
+        let addr = server(request.len()).await;
+
+        // a TCP stream takes vectored writes, so the values go out without being copied into one buffer.
+        let mut conn = Connection::connect(addr).await.unwrap();
+        let mut elapsed = Duration::ZERO;
+        for _ in 0..ITERATIONS {
+            let mut pipeline = Pipeline::new();
+            for _ in 0..COMMANDS {
+                pipeline.push([b"SET" as &[u8], b"key", &value]);
+            }
+            let start = Instant::now();
+            let replies = pipeline.execute(&mut conn).await.unwrap();
+            elapsed += start.elapsed();
+            assert_eq!(COMMANDS, replies.len());
+        }
+        report("10k SETs of 4 KB, vectored", elapsed);
+
+        // what the fallback does, every command serialized into one buffer and written in one go. The replies are
+        // read without parsing them, which slightly favours this side.
+        let mut socket = TcpStream::connect(addr).await.unwrap();
+        let mut replies = vec![0; 5 * COMMANDS];
+        let mut elapsed = Duration::ZERO;
+        for _ in 0..ITERATIONS {
+            let start = Instant::now();
+            let mut buf = BytesMut::new();
+            frames.iter().for_each(|frame| frame.serialize(&mut buf));
+            socket.write_all(&buf).await.unwrap();
+            socket.read_exact(&mut replies).await.unwrap();
+            elapsed += start.elapsed();
+        }
+        report("10k SETs of 4 KB, buffered", elapsed);
+    });
+}
@@ -0,0 +1,208 @@
+use bytes::Bytes;
+
+/// Something that can be written out as one or more command arguments. Numbers are sent as their decimal text,
+/// slices, vectors and tuples as each of their elements in turn.
+pub trait ToArg {
+    fn write_arg(&self, buf: &mut Vec<Bytes>);
+}
+
+/// A command assembled argument by argument, e.g. `conn.query::<()>(Cmd::new("SET").arg("key").arg(42))`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cmd {
+    args: Vec<Bytes>,
+}
+
+impl Cmd {
+    pub fn new(name: &str) -> Self {
+        Cmd { args: vec![Bytes::copy_from_slice(name.as_bytes())] }
+    }
+
+    pub fn arg<A>(mut self, arg: A) -> Self
+    where
+        A: ToArg,
+    {
+        arg.write_arg(&mut self.args);
+        self
+    }
+
+    /// The command name followed by its arguments.
+    pub fn args(&self) -> &[Bytes] {
+        &self.args
+    }
+}
+
+impl ToArg for Cmd {
+    fn write_arg(&self, buf: &mut Vec<Bytes>) {
+        buf.extend_from_slice(&self.args);
+    }
+}
+
+impl ToArg for str {
+    fn write_arg(&self, buf: &mut Vec<Bytes>) {
+        buf.push(Bytes::copy_from_slice(self.as_bytes()));
+    }
+}
+
+impl ToArg for String {
+    fn write_arg(&self, buf: &mut Vec<Bytes>) {
+        self.as_str().write_arg(buf);
+    }
+}
+
+impl ToArg for [u8] {
+    fn write_arg(&self, buf: &mut Vec<Bytes>) {
+        buf.push(Bytes::copy_from_slice(self));
+    }
+}
+
+impl<const N: usize> ToArg for [u8; N] {
+    fn write_arg(&self, buf: &mut Vec<Bytes>) {
+        self.as_slice().write_arg(buf);
+    }
+}
+
+impl ToArg for Vec<u8> {
+    fn write_arg(&self, buf: &mut Vec<Bytes>) {
+        self.as_slice().write_arg(buf);
+    }
+}
+
+impl ToArg for Bytes {
+    fn write_arg(&self, buf: &mut Vec<Bytes>) {
+        buf.push(self.clone());
+    }
+}
+
+macro_rules! integer_arg {
+    ($($ty:ty),*) => {
+        $(
+            impl ToArg for $ty {
+                fn write_arg(&self, buf: &mut Vec<Bytes>) {
+                    buf.push(Bytes::from(self.to_string()));
+                }
+            }
+        )*
+    };
+}
+
+integer_arg!(i32, i64, isize, u32, u64, usize);
+
+impl ToArg for f64 {
+    /// Infinities come out as `inf` and `-inf`, which is what Redis accepts for them, e.g. as `ZADD` scores.
+    fn write_arg(&self, buf: &mut Vec<Bytes>) {
+        buf.push(Bytes::from(self.to_string()));
+    }
+}
+
+impl<T> ToArg for &T
+where
+    T: ToArg + ?Sized,
+{
+    fn write_arg(&self, buf: &mut Vec<Bytes>) {
+        (**self).write_arg(buf);
+    }
+}
+
+impl<T> ToArg for [T]
+where
+    T: ToArg,
+{
+    fn write_arg(&self, buf: &mut Vec<Bytes>) {
+        self.iter().for_each(|arg| arg.write_arg(buf));
+    }
+}
+
+impl<T, const N: usize> ToArg for [T; N]
+where
+    T: ToArg,
+{
+    fn write_arg(&self, buf: &mut Vec<Bytes>) {
+        self.as_slice().write_arg(buf);
+    }
+}
+
+impl<T> ToArg for Vec<T>
+where
+    T: ToArg,
+{
+    fn write_arg(&self, buf: &mut Vec<Bytes>) {
+        self.as_slice().write_arg(buf);
+    }
+}
+
+macro_rules! tuple_arg {
+    ($($name:ident),+) => {
+        impl<$($name),+> ToArg for ($($name,)+)
+        where
+            $($name: ToArg,)+
+        {
+            #[allow(non_snake_case)]
+            fn write_arg(&self, buf: &mut Vec<Bytes>) {
+                let ($($name,)+) = self;
+                $($name.write_arg(buf);)+
+            }
+        }
+    };
+}
+
+tuple_arg!(A);
+tuple_arg!(A, B);
+tuple_arg!(A, B, C);
+tuple_arg!(A, B, C, D);
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::test::mock;
+    use crate::Frame;
+
+    fn args<A: ToArg>(arg: A) -> Vec<Bytes> {
+        let mut buf = Vec::new();
+        arg.write_arg(&mut buf);
+        buf
+    }
+
+    #[test]
+    pub fn test_mixed_args() {
+        let cmd = Cmd::new("SET").arg("key").arg(42).arg(String::from("s")).arg(b"raw").arg(1.5).arg(u64::MAX);
+
+        let expected: Vec<&[u8]> = vec![b"SET", b"key", b"42", b"s", b"raw", b"1.5", b"18446744073709551615"];
+        assert_eq!(expected, cmd.args().iter().map(|arg| &arg[..]).collect::<Vec<_>>());
+    }
+
+    #[test]
+    pub fn test_numbers() {
+        assert_eq!(vec![Bytes::from_static(b"-7")], args(-7i64));
+        assert_eq!(vec![Bytes::from_static(b"0.1")], args(0.1));
+        assert_eq!(vec![Bytes::from_static(b"3")], args(3.0));
+        assert_eq!(vec![Bytes::from_static(b"-inf")], args(f64::NEG_INFINITY));
+    }
+
+    #[test]
+    pub fn test_nested() {
+        let pairs = [(1.0, "a"), (2.5, "b")];
+        let expected: Vec<&[u8]> = vec![b"ZADD", b"z", b"1", b"a", b"2.5", b"b"];
+        assert_eq!(expected, args((Cmd::new("ZADD"), "z", &pairs[..])));
+
+        let slices: &[&[u8]] = &[b"x", b"y"];
+        assert_eq!(vec![Bytes::from_static(b"x"), Bytes::from_static(b"y")], args(slices));
+        assert_eq!(vec![Bytes::from_static(b"xy")], args(b"xy".to_vec()));
+    }
+
+    #[tokio::test]
+    pub async fn test_query_cmd() {
+        let mut conn = mock(b"*3\r\n$6\r\nINCRBY\r\n$7\r\ncounter\r\n$2\r\n10\r\n", b":12\r\n").await;
+
+        let n: i64 = conn.query(Cmd::new("INCRBY").arg("counter").arg(10)).await.unwrap();
+
+        assert_eq!(12, n);
+    }
+
+    #[tokio::test]
+    pub async fn test_query_cmd_error() {
+        let mut conn =
+            mock(b"*3\r\n$6\r\nINCRBY\r\n$7\r\ncounter\r\n$3\r\n1.5\r\n", b"-ERR value is not an integer\r\n").await;
+
+        assert!(conn.query::<Frame>(Cmd::new("INCRBY").arg("counter").arg(1.5)).await.is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{Frame, RedisError};
+
+/// Frames RESP over any byte stream, wrap one in `tokio_util::codec::Framed` to get a `Stream` of replies and a
+/// `Sink` for requests.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameCodec;
+
+impl Decoder for FrameCodec {
+    type Item = Frame;
+    type Error = RedisError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Frame>, RedisError> {
+        Ok(Frame::decode(src)?)
+    }
+}
+
+impl Encoder<Frame> for FrameCodec {
+    type Error = RedisError;
+
+    fn encode(&mut self, item: Frame, dst: &mut BytesMut) -> Result<(), RedisError> {
+        item.serialize(dst);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::frame::Error;
+
+    #[test]
+    pub fn test_decode() {
+        let mut buf = BytesMut::from(&b"+OK\r\n$3\r\nfo"[..]);
+
+        assert_eq!(Some(Frame::Simple(String::from("OK"))), FrameCodec.decode(&mut buf).unwrap());
+        assert_eq!(None, FrameCodec.decode(&mut buf).unwrap());
+        assert_eq!(b"$3\r\nfo" as &[u8], &buf[..]);
+
+        buf.extend_from_slice(b"o\r\n");
+        assert_eq!(Some(Frame::Bulk(Bytes::from_static(b"foo"))), FrameCodec.decode(&mut buf).unwrap());
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    pub fn test_decode_invalid() {
+        let mut buf = BytesMut::from(&b"?what\r\n"[..]);
+
+        assert!(matches!(FrameCodec.decode(&mut buf), Err(RedisError::Frame(Error::Protocol(_)))));
+    }
+
+    #[test]
+    pub fn test_encode() {
+        let mut buf = BytesMut::new();
+
+        let frame = Frame::Array(vec![Frame::Bulk(Bytes::from_static(b"PING"))]);
+        FrameCodec.encode(frame, &mut buf).unwrap();
+
+        assert_eq!(b"*1\r\n$4\r\nPING\r\n" as &[u8], &buf[..]);
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use bytes::Bytes;
+
+use crate::frame::{Error, Frame};
+
+/// Converts a reply frame into a Rust type.
+pub trait FromFrame: Sized {
+    fn from_frame(frame: Frame) -> Result<Self, Error>;
+
+    /// Converts the elements of an array reply, one element each unless the type spans several of them.
+    fn from_frames(frames: Vec<Frame>) -> Result<Vec<Self>, Error> {
+        frames.into_iter().map(Self::from_frame).collect()
+    }
+}
+
+impl FromFrame for Frame {
+    fn from_frame(frame: Frame) -> Result<Self, Error> {
+        Ok(frame)
+    }
+}
+
+impl FromFrame for String {
+    fn from_frame(frame: Frame) -> Result<Self, Error> {
+        match frame {
+            Frame::Simple(s) | Frame::BigNumber(s) => Ok(s),
+            Frame::Bulk(data) | Frame::Verbatim { data, .. } => {
+                String::from_utf8(data.to_vec()).map_err(|_| Error::due_to_protocol("invalid utf-8"))
+            }
+            Frame::Integer(num) => Ok(num.to_string()),
+            frame => Err(Error::unexpected(&frame)),
+        }
+    }
+}
+
+impl FromFrame for i64 {
+    fn from_frame(frame: Frame) -> Result<Self, Error> {
+        match frame {
+            Frame::Integer(num) => Ok(num),
+            Frame::Simple(_) | Frame::Bulk(_) => {
+                frame.as_str().and_then(|s| s.parse().ok()).ok_or_else(|| Error::unexpected(&frame))
+            }
+            // a big number that happens to fit in 64 bits.
+            Frame::BigNumber(ref num) => num.parse().map_err(|_| Error::unexpected(&frame)),
+            frame => Err(Error::unexpected(&frame)),
+        }
+    }
+}
+
+impl FromFrame for u64 {
+    fn from_frame(frame: Frame) -> Result<Self, Error> {
+        match frame {
+            Frame::Integer(num) => u64::try_from(num).map_err(|_| Error::unexpected(&frame)),
+            Frame::Simple(_) | Frame::Bulk(_) => {
+                frame.as_str().and_then(|s| s.parse().ok()).ok_or_else(|| Error::unexpected(&frame))
+            }
+            Frame::BigNumber(ref num) => num.parse().map_err(|_| Error::unexpected(&frame)),
+            frame => Err(Error::unexpected(&frame)),
+        }
+    }
+}
+
+impl FromFrame for f64 {
+    fn from_frame(frame: Frame) -> Result<Self, Error> {
+        match frame {
+            Frame::Double(num) => Ok(num),
+            Frame::Integer(num) => Ok(num as f64),
+            // RESP2 has no doubles, `INCRBYFLOAT` and friends reply with the number as a bulk string.
+            Frame::Simple(_) | Frame::Bulk(_) => {
+                frame.as_str().and_then(|s| s.parse().ok()).ok_or_else(|| Error::unexpected(&frame))
+            }
+            frame => Err(Error::unexpected(&frame)),
+        }
+    }
+}
+
+impl FromFrame for bool {
+    fn from_frame(frame: Frame) -> Result<Self, Error> {
+        match frame {
+            Frame::Integer(num) => Ok(num != 0),
+            Frame::Boolean(b) => Ok(b),
+            frame => Err(Error::unexpected(&frame)),
+        }
+    }
+}
+
+impl FromFrame for Bytes {
+    fn from_frame(frame: Frame) -> Result<Self, Error> {
+        match frame {
+            Frame::Bulk(data) | Frame::Verbatim { data, .. } => Ok(data),
+            Frame::Simple(s) => Ok(Bytes::from(s)),
+            frame => Err(Error::unexpected(&frame)),
+        }
+    }
+}
+
+impl FromFrame for Vec<u8> {
+    fn from_frame(frame: Frame) -> Result<Self, Error> {
+        Bytes::from_frame(frame).map(|data| data.to_vec())
+    }
+}
+
+impl<T> FromFrame for Option<T>
+where
+    T: FromFrame,
+{
+    fn from_frame(frame: Frame) -> Result<Self, Error> {
+        match frame {
+            Frame::Nil => Ok(None),
+            frame => T::from_frame(frame).map(Some),
+        }
+    }
+}
+
+impl<T> FromFrame for Vec<T>
+where
+    T: FromFrame,
+{
+    fn from_frame(frame: Frame) -> Result<Self, Error> {
+        match frame {
+            Frame::Array(array) | Frame::Set(array) => T::from_frames(array),
+            // each field and its value, for a `Vec` of pairs.
+            Frame::Map(map) => map.into_iter().map(|(k, v)| T::from_frame(Frame::Array(vec![k, v]))).collect(),
+            Frame::Nil => Ok(Vec::new()),
+            frame => Err(Error::unexpected(&frame)),
+        }
+    }
+}
+
+impl<K, V> FromFrame for (K, V)
+where
+    K: FromFrame,
+    V: FromFrame,
+{
+    fn from_frame(frame: Frame) -> Result<Self, Error> {
+        match frame {
+            Frame::Array(pair) => match <[Frame; 2]>::try_from(pair) {
+                Ok([k, v]) => Ok((K::from_frame(k)?, V::from_frame(v)?)),
+                Err(pair) => Err(Error::unexpected(&Frame::Array(pair))),
+            },
+            frame => Err(Error::unexpected(&frame)),
+        }
+    }
+
+    /// Reads an array of two element arrays, or a flat array of alternating keys and values as RESP2 sends maps.
+    fn from_frames(frames: Vec<Frame>) -> Result<Vec<Self>, Error> {
+        if frames.iter().all(|frame| matches!(frame, Frame::Array(_))) {
+            frames.into_iter().map(Self::from_frame).collect()
+        } else {
+            pairs(Frame::Array(frames))
+        }
+    }
+}
+
+impl<T> FromFrame for HashSet<T>
+where
+    T: FromFrame + Eq + Hash,
+{
+    fn from_frame(frame: Frame) -> Result<Self, Error> {
+        match frame {
+            Frame::Set(set) | Frame::Array(set) => set.into_iter().map(T::from_frame).collect(),
+            Frame::Nil => Ok(HashSet::new()),
+            frame => Err(Error::unexpected(&frame)),
+        }
+    }
+}
+
+impl<K, V> FromFrame for HashMap<K, V>
+where
+    K: FromFrame + Eq + Hash,
+    V: FromFrame,
+{
+    fn from_frame(frame: Frame) -> Result<Self, Error> {
+        pairs(frame).map(HashMap::from_iter)
+    }
+}
+
+/// Pairs up the fields of a `Map`, or of a flat array of alternating keys and values as RESP2 sends maps.
+pub(crate) fn pairs<K, V>(frame: Frame) -> Result<Vec<(K, V)>, Error>
+where
+    K: FromFrame,
+    V: FromFrame,
+{
+    match frame {
+        Frame::Map(map) => map.into_iter().map(|(k, v)| Ok((K::from_frame(k)?, V::from_frame(v)?))).collect(),
+        Frame::Array(array) if array.len() % 2 == 0 => {
+            let mut pairs = Vec::with_capacity(array.len() / 2);
+            let mut iter = array.into_iter();
+            while let (Some(k), Some(v)) = (iter.next(), iter.next()) {
+                pairs.push((K::from_frame(k)?, V::from_frame(v)?));
+            }
+            Ok(pairs)
+        }
+        Frame::Nil => Ok(Vec::new()),
+        frame => Err(Error::unexpected(&frame)),
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_string() {
+        assert_eq!("OK", String::from_frame(Frame::Simple(String::from("OK"))).unwrap());
+        assert_eq!("bulk", String::from_frame(Frame::Bulk(Bytes::from_static(b"bulk"))).unwrap());
+        assert_eq!("42", String::from_frame(Frame::Integer(42)).unwrap());
+        assert_eq!(
+            "3492890328409238509324850943850943825024385",
+            String::from_frame(Frame::BigNumber(String::from("3492890328409238509324850943850943825024385"))).unwrap()
+        );
+        assert!(String::from_frame(Frame::Bulk(Bytes::from_static(b"\xff"))).is_err());
+        assert!(String::from_frame(Frame::Nil).is_err());
+    }
+
+    #[test]
+    pub fn test_i64() {
+        assert_eq!(-7, i64::from_frame(Frame::Integer(-7)).unwrap());
+        assert_eq!(10, i64::from_frame(Frame::Bulk(Bytes::from_static(b"10"))).unwrap());
+        assert!(i64::from_frame(Frame::Bulk(Bytes::from_static(b"ten"))).is_err());
+        assert!(i64::from_frame(Frame::Array(vec![])).is_err());
+        assert_eq!(-12, i64::from_frame(Frame::BigNumber(String::from("-12"))).unwrap());
+        assert!(i64::from_frame(Frame::BigNumber(String::from("3492890328409238509324850943850943825024385"))).is_err());
+    }
+
+    #[test]
+    pub fn test_u64() {
+        assert_eq!(7, u64::from_frame(Frame::Integer(7)).unwrap());
+        assert_eq!(10, u64::from_frame(Frame::Bulk(Bytes::from_static(b"10"))).unwrap());
+        assert_eq!(u64::MAX, u64::from_frame(Frame::BigNumber(u64::MAX.to_string())).unwrap());
+        assert!(u64::from_frame(Frame::Integer(-1)).is_err());
+        assert!(u64::from_frame(Frame::Nil).is_err());
+    }
+
+    #[test]
+    pub fn test_f64() {
+        assert_eq!(1.5, f64::from_frame(Frame::Double(1.5)).unwrap());
+        assert_eq!(2.0, f64::from_frame(Frame::Integer(2)).unwrap());
+        assert_eq!(10.5, f64::from_frame(Frame::Bulk(Bytes::from_static(b"10.5"))).unwrap());
+        assert_eq!(f64::INFINITY, f64::from_frame(Frame::Bulk(Bytes::from_static(b"inf"))).unwrap());
+        assert!(f64::from_frame(Frame::Bulk(Bytes::from_static(b"ten"))).is_err());
+        assert!(f64::from_frame(Frame::Nil).is_err());
+    }
+
+    #[test]
+    pub fn test_bool() {
+        assert!(bool::from_frame(Frame::Integer(1)).unwrap());
+        assert!(!bool::from_frame(Frame::Integer(0)).unwrap());
+        assert!(bool::from_frame(Frame::Boolean(true)).unwrap());
+        assert!(bool::from_frame(Frame::Simple(String::from("OK"))).is_err());
+    }
+
+    #[test]
+    pub fn test_bytes() {
+        assert_eq!(b"bulk" as &[u8], Bytes::from_frame(Frame::Bulk(Bytes::from_static(b"bulk"))).unwrap());
+        assert_eq!(b"OK".to_vec(), Vec::<u8>::from_frame(Frame::Simple(String::from("OK"))).unwrap());
+        assert!(Bytes::from_frame(Frame::Integer(1)).is_err());
+
+        let frame = Frame::Verbatim { format: *b"txt", data: Bytes::from_static(b"Some string") };
+        assert_eq!(b"Some string" as &[u8], Bytes::from_frame(frame).unwrap());
+    }
+
+    #[test]
+    pub fn test_option() {
+        assert_eq!(None, Option::<String>::from_frame(Frame::Nil).unwrap());
+        assert_eq!(Some(1), Option::<i64>::from_frame(Frame::Integer(1)).unwrap());
+    }
+
+    #[test]
+    pub fn test_vec() {
+        let frame = Frame::Array(vec![Frame::Bulk(Bytes::from_static(b"a")), Frame::Nil]);
+        assert_eq!(vec![Some(String::from("a")), None], Vec::<Option<String>>::from_frame(frame).unwrap());
+
+        assert!(Vec::<String>::from_frame(Frame::Nil).unwrap().is_empty());
+        assert!(Vec::<String>::from_frame(Frame::Array(vec![Frame::Integer(1), Frame::Nil])).is_err());
+    }
+
+    #[test]
+    pub fn test_vec_of_pairs() {
+        let expected = vec![(String::from("a"), 1), (String::from("b"), 2)];
+
+        let source = b"*4\r\n$1\r\na\r\n:1\r\n$1\r\nb\r\n:2\r\n" as &[u8];
+        let flat = Frame::parse(&mut std::io::Cursor::new(source)).unwrap();
+        assert_eq!(expected, Vec::<(String, i64)>::from_frame(flat).unwrap());
+
+        let source = b"*2\r\n*2\r\n$1\r\na\r\n:1\r\n*2\r\n$1\r\nb\r\n:2\r\n" as &[u8];
+        let nested = Frame::parse(&mut std::io::Cursor::new(source)).unwrap();
+        assert_eq!(expected, Vec::<(String, i64)>::from_frame(nested).unwrap());
+
+        let source = b"%2\r\n+a\r\n:1\r\n+b\r\n:2\r\n" as &[u8];
+        let map = Frame::parse(&mut std::io::Cursor::new(source)).unwrap();
+        assert_eq!(expected, Vec::<(String, i64)>::from_frame(map).unwrap());
+
+        assert!(Vec::<(String, i64)>::from_frame(Frame::Array(vec![Frame::Integer(1)])).is_err());
+        assert!(Vec::<String>::from_frame(Frame::Map(vec![(Frame::Integer(1), Frame::Integer(2))])).is_err());
+    }
+
+    #[test]
+    pub fn test_pairs() {
+        let flat = Frame::Array(vec![
+            Frame::Bulk(Bytes::from_static(b"a")),
+            Frame::Integer(1),
+            Frame::Bulk(Bytes::from_static(b"b")),
+            Frame::Integer(2),
+        ]);
+        let expected = vec![(String::from("a"), 1), (String::from("b"), 2)];
+        assert_eq!(expected, pairs::<String, i64>(flat).unwrap());
+
+        let map = Frame::Map(vec![
+            (Frame::Bulk(Bytes::from_static(b"a")), Frame::Integer(1)),
+            (Frame::Bulk(Bytes::from_static(b"b")), Frame::Integer(2)),
+        ]);
+        assert_eq!(expected, pairs::<String, i64>(map).unwrap());
+
+        assert!(pairs::<String, i64>(Frame::Array(vec![Frame::Integer(1)])).is_err());
+    }
+
+    #[test]
+    pub fn test_hash_set() {
+        let source = b"~2\r\n+x\r\n+y\r\n" as &[u8];
+        let frame = Frame::parse(&mut std::io::Cursor::new(source)).unwrap();
+
+        let set = HashSet::<String>::from_frame(frame.clone()).unwrap();
+        assert_eq!(HashSet::from([String::from("x"), String::from("y")]), set);
+
+        let members = Vec::<String>::from_frame(frame).unwrap();
+        assert_eq!(vec![String::from("x"), String::from("y")], members);
+    }
+
+    #[test]
+    pub fn test_hash_map() {
+        let source = b"*4\r\n$1\r\na\r\n:1\r\n$1\r\nb\r\n:2\r\n" as &[u8];
+        let frame = Frame::parse(&mut std::io::Cursor::new(source)).unwrap();
+        let map = HashMap::<String, i64>::from_frame(frame).unwrap();
+        assert_eq!(HashMap::from([(String::from("a"), 1), (String::from("b"), 2)]), map);
+
+        let source = b"%1\r\n+a\r\n:1\r\n" as &[u8];
+        let frame = Frame::parse(&mut std::io::Cursor::new(source)).unwrap();
+        assert_eq!(HashMap::from([(String::from("a"), 1)]), HashMap::<String, i64>::from_frame(frame).unwrap());
+
+        assert!(HashMap::<String, i64>::from_frame(Frame::Array(vec![Frame::Integer(1)])).is_err());
+    }
+}
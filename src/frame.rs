@@ -1,9 +1,13 @@
 use std::io::Cursor;
 
-use bytes::{Buf, Bytes};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 
 #[derive(Debug)]
 pub enum Error {
+    /// The buffer holds the start of a frame but not all of it yet, reading more bytes will complete it.
+    Incomplete,
+
+    /// The connection was closed in the middle of a frame.
     StreamEndedEarly,
 
     Protocol(String),
@@ -12,6 +16,7 @@ pub enum Error {
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Error::Incomplete => write!(f, "incomplete frame"),
             Error::StreamEndedEarly => write!(f, "stream ended early"),
             Error::Protocol(s) => write!(f, "protocol error, {s}"),
         }
@@ -21,22 +26,47 @@ impl std::fmt::Display for Error {
 impl std::error::Error for Error {}
 
 impl Error {
-    fn due_to_protocol<S>(reason: S) -> Error
+    pub(crate) fn due_to_protocol<S>(reason: S) -> Error
     where
         S: ToString,
     {
         Error::Protocol(reason.to_string())
     }
+
+    pub(crate) fn unexpected(frame: &Frame) -> Error {
+        Error::Protocol(format!("unexpected frame `{frame:?}`"))
+    }
+}
+
+/// Upper bounds enforced while decoding, so a misbehaving peer can't exhaust the stack or the memory.
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    pub max_depth: usize,
+    pub max_array_length: u64,
+    pub max_bulk_length: u64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits { max_depth: 512, max_array_length: 4 * 1024 * 1024, max_bulk_length: 512 * 1024 * 1024 }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Frame {
     Simple(String),
     Error(String),
-    Integer(u64),
+    Integer(i64),
     Bulk(Bytes),
     Nil,
     Array(Vec<Frame>),
+    Double(f64),
+    Boolean(bool),
+    Map(Vec<(Frame, Frame)>),
+    Set(Vec<Frame>),
+    BigNumber(String),
+    Push(Vec<Frame>),
+    Verbatim { format: [u8; 3], data: Bytes },
 }
 
 impl Frame {
@@ -45,18 +75,126 @@ impl Frame {
     const INTEGERS: u8 = b':';
     const BULK: u8 = b'$';
     const ARRAY: u8 = b'*';
+    const DOUBLE: u8 = b',';
+    const BOOLEAN: u8 = b'#';
+    const MAP: u8 = b'%';
+    const SET: u8 = b'~';
+    const NULL: u8 = b'_';
+    const BIG_NUMBER: u8 = b'(';
+    const PUSH: u8 = b'>';
+    const VERBATIM: u8 = b'=';
+
+    /// Below this, copying a bulk payload is cheaper than giving it a slice of its own in a vectored write.
+    const VECTORED_BULK: usize = 1024;
 
     pub fn parse(src: &mut Cursor<&[u8]>) -> Result<Frame, Error> {
+        Frame::parse_with_limits(src, &Limits::default())
+    }
+
+    pub fn parse_with_limits(src: &mut Cursor<&[u8]>, limits: &Limits) -> Result<Frame, Error> {
+        Frame::parse_frame(src, None, limits, 0)
+    }
+
+    /// Like `parse`, but bulk payloads are slices of `src` instead of copies.
+    pub fn parse_bytes(src: &mut Cursor<Bytes>) -> Result<Frame, Error> {
+        Frame::parse_bytes_with_limits(src, &Limits::default())
+    }
+
+    pub fn parse_bytes_with_limits(src: &mut Cursor<Bytes>, limits: &Limits) -> Result<Frame, Error> {
+        let owner = src.get_ref().clone();
+        let mut cursor = Cursor::new(&owner[..]);
+        cursor.set_position(src.position());
+        let frame = Frame::parse_frame(&mut cursor, Some(&owner), limits, 0)?;
+        src.set_position(cursor.position());
+        Ok(frame)
+    }
+
+    /// Splits the first frame off `buf` once it's complete, `None` until then.
+    pub(crate) fn decode(buf: &mut BytesMut) -> Result<Option<Frame>, Error> {
+        let mut cursor = Cursor::new(&buf[..]);
+        match Frame::check(&mut cursor) {
+            Ok(()) => {
+                let len = cursor.position() as usize;
+                // bulk payloads in the frame are handed out as slices of this chunk of the buffer.
+                let data = buf.split_to(len).freeze();
+                Frame::parse_bytes(&mut Cursor::new(data)).map(Some)
+            }
+            Err(Error::Incomplete) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn check(src: &mut Cursor<&[u8]>) -> Result<(), Error> {
+        Frame::check_with_limits(src, &Limits::default())
+    }
+
+    pub fn check_with_limits(src: &mut Cursor<&[u8]>, limits: &Limits) -> Result<(), Error> {
+        Frame::check_frame(src, limits, 0)
+    }
+
+    fn parse_frame(
+        src: &mut Cursor<&[u8]>,
+        owner: Option<&Bytes>,
+        limits: &Limits,
+        depth: usize,
+    ) -> Result<Frame, Error> {
         if !src.has_remaining() {
-            return Err(Error::StreamEndedEarly);
+            return Err(Error::Incomplete);
+        }
+        if depth > limits.max_depth {
+            return Err(Error::due_to_protocol("maximum nesting depth exceeded"));
         }
         let first = src.get_u8();
         match first {
             Frame::SIMPLE => Frame::parse_simple(src),
             Frame::ERRORS => Frame::parse_error(src),
             Frame::INTEGERS => Frame::parse_integer(src),
-            Frame::BULK => Frame::parse_bulk(src),
-            Frame::ARRAY => Frame::parse_array(src),
+            Frame::BULK => Frame::parse_bulk(src, owner, limits),
+            Frame::ARRAY => Frame::parse_array(src, owner, limits, depth),
+            Frame::DOUBLE => Frame::parse_double(src),
+            Frame::BOOLEAN => Frame::parse_boolean(src),
+            Frame::MAP => Frame::parse_map(src, owner, limits, depth),
+            Frame::SET => Frame::parse_set(src, owner, limits, depth),
+            Frame::NULL => Frame::parse_null(src),
+            Frame::BIG_NUMBER => Frame::parse_big_number(src),
+            Frame::PUSH => Frame::parse_push(src, owner, limits, depth),
+            Frame::VERBATIM => Frame::parse_verbatim(src, owner, limits),
+            actual => Err(Error::due_to_protocol(format!("invalid frame type byte `{actual}`"))),
+        }
+    }
+
+    fn check_frame(src: &mut Cursor<&[u8]>, limits: &Limits, depth: usize) -> Result<(), Error> {
+        if !src.has_remaining() {
+            return Err(Error::Incomplete);
+        }
+        if depth > limits.max_depth {
+            return Err(Error::due_to_protocol("maximum nesting depth exceeded"));
+        }
+        let first = src.get_u8();
+        match first {
+            Frame::SIMPLE | Frame::ERRORS | Frame::INTEGERS | Frame::DOUBLE | Frame::BOOLEAN | Frame::BIG_NUMBER => {
+                Frame::read_line(src)?;
+                Ok(())
+            }
+            Frame::NULL => Frame::parse_null(src).map(|_| ()),
+            Frame::BULK | Frame::VERBATIM => match Frame::read_length(src, limits.max_bulk_length)? {
+                Some(length) => Frame::read_payload(src, length as usize).map(|_| ()),
+                None => Ok(()),
+            },
+            Frame::ARRAY | Frame::SET | Frame::PUSH => {
+                let length = Frame::read_length(src, limits.max_array_length)?.unwrap_or(0);
+                for _ in 0..length {
+                    Frame::check_frame(src, limits, depth + 1)?;
+                }
+                Ok(())
+            }
+            Frame::MAP => {
+                let length = Frame::read_length(src, limits.max_array_length)?.unwrap_or(0);
+                for _ in 0..length * 2 {
+                    Frame::check_frame(src, limits, depth + 1)?;
+                }
+                Ok(())
+            }
             actual => Err(Error::due_to_protocol(format!("invalid frame type byte `{actual}`"))),
         }
     }
@@ -73,58 +211,340 @@ impl Frame {
 
     fn parse_integer(src: &mut Cursor<&[u8]>) -> Result<Frame, Error> {
         let line = Frame::read_line(src)?;
-        let integer = atoi::atoi::<u64>(line).ok_or_else(|| Error::due_to_protocol("invalid frame format"))?;
+        let integer = atoi::atoi::<i64>(line).ok_or_else(|| Error::due_to_protocol("invalid frame format"))?;
         Ok(Frame::Integer(integer))
     }
 
-    fn parse_bulk(src: &mut Cursor<&[u8]>) -> Result<Frame, Error> {
-        if !src.has_remaining() {
-            return Err(Error::StreamEndedEarly);
-        }
-        let first = src.chunk()[0];
-        return match first {
-            b'-' => {
-                let line = Frame::read_line(src)?;
-                if line != b"-1" {
-                    return Err(Error::StreamEndedEarly);
-                }
-                Ok(Frame::Nil)
-            }
-            _ => {
-                let line = Frame::read_line(src)?;
-                let length = atoi::atoi::<u64>(line).ok_or_else(|| Error::due_to_protocol("invalid frame format"))?;
-                let n = (length + 2) as usize;
-                if src.remaining() < n {
-                    return Err(Error::StreamEndedEarly);
-                }
-                let data = Bytes::copy_from_slice(&src.chunk()[..length as usize]);
-                src.advance(n);
-                Ok(Frame::Bulk(data))
-            }
+    fn parse_bulk(src: &mut Cursor<&[u8]>, owner: Option<&Bytes>, limits: &Limits) -> Result<Frame, Error> {
+        let length = match Frame::read_length(src, limits.max_bulk_length)? {
+            Some(length) => length,
+            None => return Ok(Frame::Nil),
         };
+        let data = Frame::read_payload(src, length as usize)?;
+        Ok(Frame::Bulk(match owner {
+            Some(owner) => owner.slice_ref(data),
+            None => Bytes::copy_from_slice(data),
+        }))
+    }
+
+    /// The `length` bytes of a bulk payload, which have to be followed by `\r\n`.
+    fn read_payload<'a>(src: &mut Cursor<&'a [u8]>, length: usize) -> Result<&'a [u8], Error> {
+        let start = src.position() as usize;
+        let buf: &'a [u8] = src.get_ref();
+        let rest = buf.get(start..).unwrap_or(&[]);
+        if rest.len() < length + 2 {
+            return Err(Error::Incomplete);
+        }
+        if &rest[length..length + 2] != b"\r\n" {
+            return Err(Error::due_to_protocol("bulk payload is not terminated by CRLF"));
+        }
+        src.set_position((start + length + 2) as u64);
+        Ok(&rest[..length])
     }
 
-    fn parse_array(src: &mut Cursor<&[u8]>) -> Result<Frame, Error> {
+    /// A bulk string whose first four bytes are a format hint like `txt:` or `mkd:`.
+    fn parse_verbatim(src: &mut Cursor<&[u8]>, owner: Option<&Bytes>, limits: &Limits) -> Result<Frame, Error> {
+        let data = match Frame::parse_bulk(src, owner, limits)? {
+            Frame::Bulk(data) => data,
+            frame => return Ok(frame),
+        };
+        if data.len() < 4 || data[3] != b':' {
+            return Err(Error::due_to_protocol("invalid verbatim string"));
+        }
+        let format = [data[0], data[1], data[2]];
+        Ok(Frame::Verbatim { format, data: data.slice(4..) })
+    }
+
+    fn parse_array(
+        src: &mut Cursor<&[u8]>,
+        owner: Option<&Bytes>,
+        limits: &Limits,
+        depth: usize,
+    ) -> Result<Frame, Error> {
+        Ok(Frame::parse_elements(src, owner, limits, depth)?.map_or(Frame::Nil, Frame::Array))
+    }
+
+    fn parse_set(
+        src: &mut Cursor<&[u8]>,
+        owner: Option<&Bytes>,
+        limits: &Limits,
+        depth: usize,
+    ) -> Result<Frame, Error> {
+        Ok(Frame::parse_elements(src, owner, limits, depth)?.map_or(Frame::Nil, Frame::Set))
+    }
+
+    /// Out-of-band data the server sends on its own, like pub/sub messages and client-side cache invalidations.
+    fn parse_push(
+        src: &mut Cursor<&[u8]>,
+        owner: Option<&Bytes>,
+        limits: &Limits,
+        depth: usize,
+    ) -> Result<Frame, Error> {
+        Ok(Frame::parse_elements(src, owner, limits, depth)?.map_or(Frame::Nil, Frame::Push))
+    }
+
+    /// Parses the length line and the elements of an aggregate, `None` stands for the null aggregate.
+    fn parse_elements(
+        src: &mut Cursor<&[u8]>,
+        owner: Option<&Bytes>,
+        limits: &Limits,
+        depth: usize,
+    ) -> Result<Option<Vec<Frame>>, Error> {
+        let length = match Frame::read_length(src, limits.max_array_length)? {
+            Some(length) => length,
+            None => return Ok(None),
+        };
+        // every element takes at least one byte, so never reserve more than what has been received.
+        let mut elements = Vec::with_capacity((length as usize).min(src.remaining()));
+
+        for _ in 0..length {
+            elements.push(Frame::parse_frame(src, owner, limits, depth + 1)?);
+        }
+        Ok(Some(elements))
+    }
+
+    /// Reads the length line of a bulk string or an aggregate, `None` stands for the `-1` null marker.
+    fn read_length(src: &mut Cursor<&[u8]>, max: u64) -> Result<Option<u64>, Error> {
         let line = Frame::read_line(src)?;
+        if line == b"-1" {
+            return Ok(None);
+        }
+        if line.starts_with(b"-") {
+            return Err(Error::due_to_protocol("invalid length"));
+        }
         let length = atoi::atoi::<u64>(line).ok_or_else(|| Error::due_to_protocol("invalid frame format"))?;
-        let mut array = Vec::with_capacity(length as usize);
+        if length > max {
+            return Err(Error::due_to_protocol(format!("length {length} exceeds the limit {max}")));
+        }
+        Ok(Some(length))
+    }
+
+    fn parse_map(
+        src: &mut Cursor<&[u8]>,
+        owner: Option<&Bytes>,
+        limits: &Limits,
+        depth: usize,
+    ) -> Result<Frame, Error> {
+        let length = match Frame::read_length(src, limits.max_array_length)? {
+            Some(length) => length,
+            None => return Ok(Frame::Nil),
+        };
+        let mut map = Vec::with_capacity((length as usize).min(src.remaining()));
 
         for _ in 0..length {
-            array.push(Frame::parse(src)?);
+            let key = Frame::parse_frame(src, owner, limits, depth + 1)?;
+            let value = Frame::parse_frame(src, owner, limits, depth + 1)?;
+            map.push((key, value));
+        }
+        Ok(Frame::Map(map))
+    }
+
+    fn parse_double(src: &mut Cursor<&[u8]>) -> Result<Frame, Error> {
+        let line = Frame::read_line(src)?;
+        let double = match line {
+            b"inf" => f64::INFINITY,
+            b"-inf" => f64::NEG_INFINITY,
+            b"nan" => f64::NAN,
+            // `str::parse` also takes spellings like `infinity` or `NaN`, which aren't valid RESP3.
+            _ if line.iter().all(|b| b.is_ascii_digit() || b"+-.eE".contains(b)) => std::str::from_utf8(line)
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .ok_or_else(|| Error::due_to_protocol("invalid frame format"))?,
+            _ => return Err(Error::due_to_protocol("invalid frame format")),
+        };
+        Ok(Frame::Double(double))
+    }
+
+    /// The RESP3 null, it's decoded to the same `Nil` as the RESP2 null bulk string and null array.
+    fn parse_null(src: &mut Cursor<&[u8]>) -> Result<Frame, Error> {
+        if !Frame::read_line(src)?.is_empty() {
+            return Err(Error::due_to_protocol("invalid frame format"));
+        }
+        Ok(Frame::Nil)
+    }
+
+    fn parse_boolean(src: &mut Cursor<&[u8]>) -> Result<Frame, Error> {
+        match Frame::read_line(src)? {
+            b"t" => Ok(Frame::Boolean(true)),
+            b"f" => Ok(Frame::Boolean(false)),
+            _ => Err(Error::due_to_protocol("invalid frame format")),
+        }
+    }
+
+    /// Big numbers are kept as text, there's no bignum type to decode them into.
+    fn parse_big_number(src: &mut Cursor<&[u8]>) -> Result<Frame, Error> {
+        let line = Frame::read_line(src)?;
+        let digits = line.strip_prefix(b"-").or_else(|| line.strip_prefix(b"+")).unwrap_or(line);
+        if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+            return Err(Error::due_to_protocol("invalid frame format"));
         }
-        Ok(Frame::Array(array))
+        Ok(Frame::BigNumber(String::from_utf8_lossy(line).into()))
     }
 
     fn read_line<'a>(src: &mut Cursor<&'a [u8]>) -> Result<&'a [u8], Error> {
         let start = src.position() as usize;
-        let end = src.get_ref().len() - 1;
-        for i in start..end {
-            if src.get_ref()[i] == b'\r' && src.get_ref()[i + 1] == b'\n' {
-                src.set_position((i + 2) as u64);
-                return Ok(&src.get_ref()[start..i]);
+        let buf: &'a [u8] = src.get_ref();
+        let rest = buf.get(start..).unwrap_or(&[]);
+        let mut from = 0;
+        while let Some(i) = memchr::memchr(b'\r', &rest[from..]).map(|i| from + i) {
+            if rest.get(i + 1) == Some(&b'\n') {
+                src.set_position((start + i + 2) as u64);
+                return Ok(&rest[..i]);
             }
+            from = i + 1;
+        }
+        Err(Error::Incomplete)
+    }
+
+    /// The text of a `Simple` or `Bulk` frame, `None` for other frames or if the bulk isn't valid UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Frame::Simple(s) => Some(s),
+            Frame::Bulk(data) | Frame::Verbatim { data, .. } => std::str::from_utf8(data).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Frame::Bulk(data) | Frame::Verbatim { data, .. } => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Frame::Integer(num) => Some(*num),
+            _ => None,
         }
-        Err(Error::StreamEndedEarly)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Frame::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Looks up the value of a `Map` frame by a textual key.
+    pub fn get(&self, key: &str) -> Option<&Frame> {
+        match self {
+            Frame::Map(map) => map.iter().find(|(k, _)| k.as_str() == Some(key)).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn is_nil(&self) -> bool {
+        matches!(self, Frame::Nil)
+    }
+
+    /// Whether the frame is an out-of-band push rather than the reply of a command.
+    pub fn is_push(&self) -> bool {
+        matches!(self, Frame::Push(_))
+    }
+
+    pub fn serialize(&self, dst: &mut BytesMut) {
+        match self {
+            Frame::Simple(s) => {
+                dst.put_u8(Frame::SIMPLE);
+                Frame::write_line(dst, s.as_bytes());
+            }
+            Frame::Error(e) => {
+                dst.put_u8(Frame::ERRORS);
+                Frame::write_line(dst, e.as_bytes());
+            }
+            Frame::Integer(num) => {
+                dst.put_u8(Frame::INTEGERS);
+                Frame::write_decimal(dst, num);
+            }
+            Frame::Bulk(data) => {
+                dst.put_u8(Frame::BULK);
+                Frame::write_decimal(dst, data.len());
+                Frame::write_line(dst, data);
+            }
+            Frame::Nil => dst.put_slice(b"$-1\r\n"),
+            Frame::Array(array) => Frame::write_elements(dst, Frame::ARRAY, array),
+            Frame::Double(num) => {
+                dst.put_u8(Frame::DOUBLE);
+                if num.is_nan() {
+                    Frame::write_line(dst, b"nan");
+                } else {
+                    Frame::write_decimal(dst, num);
+                }
+            }
+            Frame::Boolean(b) => {
+                dst.put_u8(Frame::BOOLEAN);
+                Frame::write_line(dst, if *b { b"t" } else { b"f" });
+            }
+            Frame::Set(set) => Frame::write_elements(dst, Frame::SET, set),
+            Frame::Push(push) => Frame::write_elements(dst, Frame::PUSH, push),
+            Frame::Verbatim { format, data } => {
+                dst.put_u8(Frame::VERBATIM);
+                Frame::write_decimal(dst, data.len() + 4);
+                dst.put_slice(format);
+                dst.put_u8(b':');
+                Frame::write_line(dst, data);
+            }
+            Frame::BigNumber(num) => {
+                dst.put_u8(Frame::BIG_NUMBER);
+                Frame::write_line(dst, num.as_bytes());
+            }
+            Frame::Map(map) => {
+                dst.put_u8(Frame::MAP);
+                Frame::write_decimal(dst, map.len());
+                for (key, value) in map {
+                    key.serialize(dst);
+                    value.serialize(dst);
+                }
+            }
+        }
+    }
+
+    /// Like `serialize`, but a bulk payload of at least `VECTORED_BULK` bytes is pushed onto `segments` as it is
+    /// rather than copied into `dst`. Whatever sits in `dst` before it is split off and pushed first, so the
+    /// segments followed by what's left in `dst` make up the frame.
+    pub(crate) fn serialize_segments(&self, dst: &mut BytesMut, segments: &mut Vec<Bytes>) {
+        match self {
+            Frame::Bulk(data) if data.len() >= Frame::VECTORED_BULK => {
+                dst.put_u8(Frame::BULK);
+                Frame::write_decimal(dst, data.len());
+                segments.push(dst.split().freeze());
+                segments.push(data.clone());
+                dst.put_slice(b"\r\n");
+            }
+            Frame::Array(array) => Frame::write_element_segments(dst, segments, Frame::ARRAY, array),
+            Frame::Set(set) => Frame::write_element_segments(dst, segments, Frame::SET, set),
+            Frame::Push(push) => Frame::write_element_segments(dst, segments, Frame::PUSH, push),
+            frame => frame.serialize(dst),
+        }
+    }
+
+    fn write_element_segments(dst: &mut BytesMut, segments: &mut Vec<Bytes>, kind: u8, elements: &[Frame]) {
+        dst.put_u8(kind);
+        Frame::write_decimal(dst, elements.len());
+        for frame in elements {
+            frame.serialize_segments(dst, segments);
+        }
+    }
+
+    fn write_elements(dst: &mut BytesMut, kind: u8, elements: &[Frame]) {
+        dst.put_u8(kind);
+        Frame::write_decimal(dst, elements.len());
+        for frame in elements {
+            frame.serialize(dst);
+        }
+    }
+
+    fn write_decimal<N>(buf: &mut BytesMut, num: N)
+    where
+        N: ToString,
+    {
+        Frame::write_line(buf, num.to_string().as_bytes());
+    }
+
+    fn write_line(buf: &mut BytesMut, line: &[u8]) {
+        buf.put_slice(line);
+        buf.put_slice(b"\r\n");
     }
 }
 
@@ -134,8 +554,60 @@ impl std::fmt::Display for Frame {
             Frame::Simple(s) => s.fmt(f),
             Frame::Error(e) => write!(f, "error: {}", e),
             Frame::Integer(num) => num.fmt(f),
-            _ => todo!(),
+            Frame::Bulk(data) | Frame::Verbatim { data, .. } => String::from_utf8_lossy(data).fmt(f),
+            Frame::Nil => write!(f, "(nil)"),
+            Frame::Double(num) => num.fmt(f),
+            Frame::Boolean(b) => b.fmt(f),
+            Frame::BigNumber(num) => num.fmt(f),
+            Frame::Array(array) => fmt_elements(array, ')', f),
+            Frame::Set(set) => fmt_elements(set, '~', f),
+            Frame::Push(push) => fmt_elements(push, ')', f),
+            Frame::Map(map) => {
+                if map.is_empty() {
+                    return write!(f, "(empty hash)");
+                }
+                for (i, (key, value)) in map.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}# ", i + 1)?;
+                    fmt_nested(key, f)?;
+                    write!(f, " => ")?;
+                    fmt_nested(value, f)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn fmt_elements(elements: &[Frame], marker: char, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if elements.is_empty() {
+        return write!(f, "(empty array)");
+    }
+    for (i, frame) in elements.iter().enumerate() {
+        if i > 0 {
+            writeln!(f)?;
+        }
+        write!(f, "{}{marker} ", i + 1)?;
+        fmt_nested(frame, f)?;
+    }
+    Ok(())
+}
+
+/// Formats a frame that sits inside an aggregate, with the type hints redis-cli prints.
+fn fmt_nested(frame: &Frame, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match frame {
+        Frame::Error(e) => write!(f, "(error) {e}"),
+        Frame::Integer(num) => write!(f, "(integer) {num}"),
+        Frame::Double(num) => write!(f, "(double) {num}"),
+        Frame::BigNumber(num) => write!(f, "(big number) {num}"),
+        Frame::Boolean(b) => write!(f, "({b})"),
+        Frame::Bulk(data) => write!(f, "{:?}", String::from_utf8_lossy(data)),
+        Frame::Array(_) | Frame::Map(_) | Frame::Set(_) | Frame::Push(_) => {
+            write!(f, "{}", frame.to_string().replace('\n', "\n   "))
         }
+        frame => write!(f, "{frame}"),
     }
 }
 
@@ -173,6 +645,114 @@ pub mod test {
         assert_eq!(Frame::Integer(791), frame);
     }
 
+    #[test]
+    pub fn test_parse_negative_integer() {
+        let source = b":-1\r\n" as &[u8];
+        assert_eq!(Frame::Integer(-1), Frame::parse(&mut Cursor::new(source)).unwrap());
+
+        let source = b":-42\r\n" as &[u8];
+        let frame = Frame::parse(&mut Cursor::new(source)).unwrap();
+        assert_eq!(Frame::Integer(-42), frame);
+        assert_eq!("-42", frame.to_string());
+
+        let source = b":-9223372036854775808\r\n" as &[u8];
+        assert_eq!(Frame::Integer(i64::MIN), Frame::parse(&mut Cursor::new(source)).unwrap());
+    }
+
+    #[test]
+    pub fn test_parse_double() {
+        let source = b",1.5\r\n" as &[u8];
+        assert_eq!(Frame::Double(1.5), Frame::parse(&mut Cursor::new(source)).unwrap());
+
+        let source = b",inf\r\n" as &[u8];
+        assert_eq!(Frame::Double(f64::INFINITY), Frame::parse(&mut Cursor::new(source)).unwrap());
+
+        let source = b",-inf\r\n" as &[u8];
+        assert_eq!(Frame::Double(f64::NEG_INFINITY), Frame::parse(&mut Cursor::new(source)).unwrap());
+
+        let source = b",nan\r\n" as &[u8];
+        assert!(matches!(Frame::parse(&mut Cursor::new(source)).unwrap(), Frame::Double(num) if num.is_nan()));
+
+        let source = b",1.5e3\r\n" as &[u8];
+        assert_eq!(Frame::Double(1500.0), Frame::parse(&mut Cursor::new(source)).unwrap());
+
+        let source = b",pi\r\n" as &[u8];
+        assert!(matches!(Frame::parse(&mut Cursor::new(source)), Err(Error::Protocol(_))));
+
+        let source = b",infinity\r\n" as &[u8];
+        assert!(matches!(Frame::parse(&mut Cursor::new(source)), Err(Error::Protocol(_))));
+
+        let source = b",1.2.3\r\n" as &[u8];
+        assert!(matches!(Frame::parse(&mut Cursor::new(source)), Err(Error::Protocol(_))));
+    }
+
+    #[test]
+    pub fn test_display_boolean() {
+        assert_eq!("true", Frame::Boolean(true).to_string());
+        assert_eq!("1) (false)", Frame::Array(vec![Frame::Boolean(false)]).to_string());
+    }
+
+    #[test]
+    pub fn test_display_double() {
+        assert_eq!("1.5", Frame::Double(1.5).to_string());
+        assert_eq!("-inf", Frame::Double(f64::NEG_INFINITY).to_string());
+        assert_eq!("1) (double) 1.5", Frame::Array(vec![Frame::Double(1.5)]).to_string());
+    }
+
+    #[test]
+    pub fn test_parse_boolean() {
+        let source = b"#t\r\n" as &[u8];
+        assert_eq!(Frame::Boolean(true), Frame::parse(&mut Cursor::new(source)).unwrap());
+
+        let source = b"#f\r\n" as &[u8];
+        assert_eq!(Frame::Boolean(false), Frame::parse(&mut Cursor::new(source)).unwrap());
+
+        let mut source = Cursor::new(b"#t\r\n" as &[u8]);
+        Frame::check(&mut source).unwrap();
+        assert_eq!(4, source.position());
+
+        let source = b"#x\r\n" as &[u8];
+        assert!(matches!(Frame::parse(&mut Cursor::new(source)), Err(Error::Protocol(_))));
+    }
+
+    #[test]
+    pub fn test_parse_big_number() {
+        let source = b"(3492890328409238509324850943850943825024385\r\n" as &[u8];
+        assert_eq!(
+            Frame::BigNumber(String::from("3492890328409238509324850943850943825024385")),
+            Frame::parse(&mut Cursor::new(source)).unwrap()
+        );
+
+        let source = b"(-3492890328409238509324850943850943825024385\r\n" as &[u8];
+        assert_eq!(
+            Frame::BigNumber(String::from("-3492890328409238509324850943850943825024385")),
+            Frame::parse(&mut Cursor::new(source)).unwrap()
+        );
+
+        let source = b"(12a\r\n" as &[u8];
+        assert!(matches!(Frame::parse(&mut Cursor::new(source)), Err(Error::Protocol(_))));
+
+        let source = b"(-\r\n" as &[u8];
+        assert!(matches!(Frame::parse(&mut Cursor::new(source)), Err(Error::Protocol(_))));
+    }
+
+    #[test]
+    pub fn test_parse_verbatim() {
+        let source = b"=15\r\ntxt:Some string\r\n" as &[u8];
+
+        let frame = Frame::parse(&mut Cursor::new(source)).unwrap();
+
+        assert_eq!(Frame::Verbatim { format: *b"txt", data: Bytes::from_static(b"Some string") }, frame);
+        assert_eq!(Some("Some string"), frame.as_str());
+        assert_eq!(Some(b"Some string" as &[u8]), frame.as_bytes());
+
+        let source = b"=4\r\ntxt-\r\n" as &[u8];
+        assert!(matches!(Frame::parse(&mut Cursor::new(source)), Err(Error::Protocol(_))));
+
+        let source = b"=15\r\ntxt:Some" as &[u8];
+        assert!(matches!(Frame::check(&mut Cursor::new(source)), Err(Error::Incomplete)));
+    }
+
     #[test]
     pub fn test_parse_bulk() {
         let source = b"$11\r\nHello world\r\n" as &[u8];
@@ -183,6 +763,26 @@ pub mod test {
         assert_eq!(Frame::Bulk(Bytes::from(b"Hello world" as &[u8])), frame);
     }
 
+    #[test]
+    pub fn test_parse_invalid_bulk_length() {
+        let source = b"$-5\r\n" as &[u8];
+
+        assert!(matches!(Frame::parse(&mut Cursor::new(source)), Err(Error::Protocol(_))));
+        assert!(matches!(Frame::check(&mut Cursor::new(source)), Err(Error::Protocol(_))));
+    }
+
+    #[test]
+    pub fn test_parse_nil() {
+        let source = b"$-1\r\n" as &[u8];
+        assert_eq!(Frame::Nil, Frame::parse(&mut Cursor::new(source)).unwrap());
+
+        let source = b"$-2\r\n" as &[u8];
+        assert!(matches!(Frame::parse(&mut Cursor::new(source)), Err(Error::Protocol(_))));
+
+        let source = b"$-" as &[u8];
+        assert!(matches!(Frame::parse(&mut Cursor::new(source)), Err(Error::Incomplete)));
+    }
+
     #[test]
     pub fn test_parse_array() {
         let source = b"*2\r\n+one\r\n+two\r\n" as &[u8];
@@ -192,4 +792,437 @@ pub mod test {
 
         assert_eq!(Frame::Array(vec![Frame::Simple("one".to_owned()), Frame::Simple("two".to_owned()),]), frame);
     }
+
+    #[test]
+    pub fn test_parse_map() {
+        let source = b"%1\r\n+key\r\n+val\r\n" as &[u8];
+
+        let frame = Frame::parse(&mut Cursor::new(source)).unwrap();
+
+        assert_eq!(Frame::Map(vec![(Frame::Simple(String::from("key")), Frame::Simple(String::from("val")))]), frame);
+        assert_eq!(Some(&Frame::Simple(String::from("val"))), frame.get("key"));
+        assert_eq!(None, frame.get("missing"));
+    }
+
+    #[test]
+    pub fn test_parse_nested_map() {
+        let source = b"%2\r\n+a\r\n:1\r\n+b\r\n:2\r\n" as &[u8];
+        assert_eq!(
+            Frame::Map(vec![
+                (Frame::Simple(String::from("a")), Frame::Integer(1)),
+                (Frame::Simple(String::from("b")), Frame::Integer(2)),
+            ]),
+            Frame::parse(&mut Cursor::new(source)).unwrap()
+        );
+
+        let source = b"%2\r\n+map\r\n%1\r\n+k\r\n:1\r\n+array\r\n*1\r\n:2\r\n" as &[u8];
+        let frame = Frame::parse(&mut Cursor::new(source)).unwrap();
+        assert_eq!(Some(&Frame::Integer(1)), frame.get("map").and_then(|map| map.get("k")));
+        assert_eq!(Some(&Frame::Array(vec![Frame::Integer(2)])), frame.get("array"));
+    }
+
+    #[test]
+    pub fn test_check_partial_map() {
+        let source = b"%2\r\n+a\r\n:1\r\n+b\r\n" as &[u8];
+
+        assert!(matches!(Frame::check(&mut Cursor::new(source)), Err(Error::Incomplete)));
+    }
+
+    #[test]
+    pub fn test_parse_set() {
+        let source = b"~2\r\n+a\r\n+b\r\n" as &[u8];
+
+        let frame = Frame::parse(&mut Cursor::new(source)).unwrap();
+
+        assert_eq!(Frame::Set(vec![Frame::Simple(String::from("a")), Frame::Simple(String::from("b"))]), frame);
+        assert_ne!(Frame::Array(vec![Frame::Simple(String::from("a")), Frame::Simple(String::from("b"))]), frame);
+    }
+
+    #[test]
+    pub fn test_parse_null() {
+        let source = b"_\r\n" as &[u8];
+        assert_eq!(Frame::Nil, Frame::parse(&mut Cursor::new(source)).unwrap());
+
+        let source = b"*2\r\n_\r\n:1\r\n" as &[u8];
+        assert_eq!(Frame::Array(vec![Frame::Nil, Frame::Integer(1)]), Frame::parse(&mut Cursor::new(source)).unwrap());
+
+        let source = b"_\r" as &[u8];
+        assert!(matches!(Frame::check(&mut Cursor::new(source)), Err(Error::Incomplete)));
+
+        let source = b"_1\r\n" as &[u8];
+        assert!(matches!(Frame::parse(&mut Cursor::new(source)), Err(Error::Protocol(_))));
+        assert!(matches!(Frame::check(&mut Cursor::new(source)), Err(Error::Protocol(_))));
+    }
+
+    #[test]
+    pub fn test_parse_empty_array() {
+        let source = b"*0\r\n" as &[u8];
+        assert_eq!(Frame::Array(vec![]), Frame::parse(&mut Cursor::new(source)).unwrap());
+
+        let source = b"*3\r\n*0\r\n*-1\r\n$0\r\n\r\n" as &[u8];
+        assert_eq!(
+            Frame::Array(vec![Frame::Array(vec![]), Frame::Nil, Frame::Bulk(Bytes::new())]),
+            Frame::parse(&mut Cursor::new(source)).unwrap()
+        );
+    }
+
+    #[test]
+    pub fn test_parse_push() {
+        let source = b">3\r\n$7\r\nmessage\r\n$2\r\nch\r\n$2\r\nhi\r\n" as &[u8];
+
+        let frame = Frame::parse(&mut Cursor::new(source)).unwrap();
+
+        assert_eq!(
+            Frame::Push(vec![
+                Frame::Bulk(Bytes::from_static(b"message")),
+                Frame::Bulk(Bytes::from_static(b"ch")),
+                Frame::Bulk(Bytes::from_static(b"hi")),
+            ]),
+            frame
+        );
+    }
+
+    #[test]
+    pub fn test_is_push() {
+        let source = b">3\r\n$7\r\nmessage\r\n$3\r\nch1\r\n$5\r\nhello\r\n" as &[u8];
+        let frame = Frame::parse(&mut Cursor::new(source)).unwrap();
+        assert!(frame.is_push());
+
+        let source = b"*3\r\n$7\r\nmessage\r\n$3\r\nch1\r\n$5\r\nhello\r\n" as &[u8];
+        let frame = Frame::parse(&mut Cursor::new(source)).unwrap();
+        assert!(!frame.is_push());
+    }
+
+    #[test]
+    pub fn test_parse_null_array() {
+        let source = b"*-1\r\n" as &[u8];
+
+        assert_eq!(Frame::Nil, Frame::parse(&mut Cursor::new(source)).unwrap());
+        assert!(Frame::check(&mut Cursor::new(source)).is_ok());
+    }
+
+    #[test]
+    pub fn test_parse_oversized_array() {
+        let source = b"*4294967295\r\n" as &[u8];
+
+        assert!(matches!(Frame::parse(&mut Cursor::new(source)), Err(Error::Protocol(_))));
+        assert!(matches!(Frame::check(&mut Cursor::new(source)), Err(Error::Protocol(_))));
+    }
+
+    #[test]
+    pub fn test_parse_nesting_limit() {
+        let limits = Limits { max_depth: 2, ..Limits::default() };
+
+        let source = b"*1\r\n*1\r\n:1\r\n" as &[u8];
+        assert!(Frame::parse_with_limits(&mut Cursor::new(source), &limits).is_ok());
+        assert!(Frame::check_with_limits(&mut Cursor::new(source), &limits).is_ok());
+
+        let source = b"*1\r\n*1\r\n*1\r\n:1\r\n" as &[u8];
+        assert!(matches!(Frame::parse_with_limits(&mut Cursor::new(source), &limits), Err(Error::Protocol(_))));
+        assert!(matches!(Frame::check_with_limits(&mut Cursor::new(source), &limits), Err(Error::Protocol(_))));
+    }
+
+    #[test]
+    pub fn test_read_line_at_end_of_buffer() {
+        let source = b"xx+OK\r\n" as &[u8];
+        let mut source = Cursor::new(&source[2..]);
+
+        let frame = Frame::parse(&mut source).unwrap();
+
+        assert_eq!(Frame::Simple(String::from("OK")), frame);
+        assert_eq!(5, source.position());
+    }
+
+    #[test]
+    pub fn test_read_line_short_buffer() {
+        assert!(matches!(Frame::read_line(&mut Cursor::new(b"" as &[u8])), Err(Error::Incomplete)));
+        assert!(matches!(Frame::read_line(&mut Cursor::new(b"\r" as &[u8])), Err(Error::Incomplete)));
+        assert_eq!(b"" as &[u8], Frame::read_line(&mut Cursor::new(b"\r\n" as &[u8])).unwrap());
+    }
+
+    #[test]
+    pub fn test_read_line_lone_carriage_return() {
+        let mut source = Cursor::new(b"a\rb\r\nc" as &[u8]);
+
+        assert_eq!(b"a\rb" as &[u8], Frame::read_line(&mut source).unwrap());
+        assert_eq!(5, source.position());
+    }
+
+    #[test]
+    pub fn test_parse_large_bulk() {
+        let mut source = b"$1048576\r\n".to_vec();
+        source.resize(source.len() + (1 << 20), b'x');
+        source.extend(b"\r\n");
+
+        let frame = Frame::parse(&mut Cursor::new(&source[..])).unwrap();
+
+        assert_eq!(Some(1 << 20), frame.as_bytes().map(<[u8]>::len));
+    }
+
+    #[test]
+    pub fn test_parse_bytes_zero_copy() {
+        let mut source = b"*2\r\n$10485760\r\n".to_vec();
+        source.resize(source.len() + (10 << 20), b'x');
+        source.extend(b"\r\n=8\r\ntxt:text\r\n");
+        let source = Bytes::from(source);
+        let range = source.as_ptr_range();
+
+        let mut cursor = Cursor::new(source.clone());
+        let frame = Frame::parse_bytes(&mut cursor).unwrap();
+
+        assert_eq!(source.len() as u64, cursor.position());
+        let Frame::Array(elements) = frame else { panic!("expected an array") };
+        // both payloads point into the source instead of a copy of it.
+        assert_eq!(10 << 20, elements[0].as_bytes().unwrap().len());
+        assert!(range.contains(&elements[0].as_bytes().unwrap().as_ptr()));
+        assert_eq!(Some("text"), elements[1].as_str());
+        assert!(range.contains(&elements[1].as_bytes().unwrap().as_ptr()));
+    }
+
+    #[test]
+    pub fn test_parse_bytes_from_position() {
+        let mut cursor = Cursor::new(Bytes::from_static(b"+OK\r\n$3\r\nfoo\r\n"));
+        cursor.set_position(5);
+
+        assert_eq!(Frame::Bulk(Bytes::from_static(b"foo")), Frame::parse_bytes(&mut cursor).unwrap());
+        assert_eq!(14, cursor.position());
+        assert!(matches!(Frame::parse_bytes(&mut cursor), Err(Error::Incomplete)));
+    }
+
+    #[test]
+    pub fn test_check_complete() {
+        let source = b"*2\r\n$5\r\nhello\r\n:1\r\n+rest" as &[u8];
+        let mut source = Cursor::new(source);
+
+        Frame::check(&mut source).unwrap();
+
+        assert_eq!(19, source.position());
+    }
+
+    #[test]
+    pub fn test_check_partial_bulk() {
+        let source = b"$5\r\nab" as &[u8];
+        let mut source = Cursor::new(source);
+
+        assert!(matches!(Frame::check(&mut source), Err(Error::Incomplete)));
+    }
+
+    #[test]
+    pub fn test_check_bulk() {
+        let mut source = Cursor::new(b"$5\r\nabcde\r\n" as &[u8]);
+
+        Frame::check(&mut source).unwrap();
+
+        assert_eq!(11, source.position());
+    }
+
+    #[test]
+    pub fn test_check_bulk_bad_terminator() {
+        let source = b"$5\r\nabcdeXY" as &[u8];
+
+        assert!(matches!(Frame::check(&mut Cursor::new(source)), Err(Error::Protocol(_))));
+        assert!(matches!(Frame::parse(&mut Cursor::new(source)), Err(Error::Protocol(_))));
+        assert!(matches!(Frame::check(&mut Cursor::new(b"$5\r\nabcde\r" as &[u8])), Err(Error::Incomplete)));
+    }
+
+    #[test]
+    pub fn test_check_partial_array() {
+        let source = b"*3\r\n+one\r\n$3\r\ntwo\r\n" as &[u8];
+        let mut source = Cursor::new(source);
+
+        assert!(matches!(Frame::check(&mut source), Err(Error::Incomplete)));
+    }
+
+    fn assert_round_trip(source: &[u8]) {
+        let frame = Frame::parse(&mut Cursor::new(source)).unwrap();
+
+        let mut buf = BytesMut::new();
+        frame.serialize(&mut buf);
+
+        assert_eq!(source, &buf[..]);
+    }
+
+    #[test]
+    pub fn test_serialize_simple() {
+        assert_round_trip(b"+PONG\r\n");
+    }
+
+    #[test]
+    pub fn test_serialize_error() {
+        assert_round_trip(b"-ERR unknown command\r\n");
+    }
+
+    #[test]
+    pub fn test_serialize_integer() {
+        assert_round_trip(b":791\r\n");
+        assert_round_trip(b":-9223372036854775808\r\n");
+    }
+
+    #[test]
+    pub fn test_serialize_bulk() {
+        assert_round_trip(b"$11\r\nHello world\r\n");
+        assert_round_trip(b"$0\r\n\r\n");
+    }
+
+    #[test]
+    pub fn test_serialize_double() {
+        assert_round_trip(b",3.14\r\n");
+        assert_round_trip(b",-1.5\r\n");
+        assert_round_trip(b",inf\r\n");
+        assert_round_trip(b",-inf\r\n");
+        assert_round_trip(b",nan\r\n");
+    }
+
+    #[test]
+    pub fn test_serialize_boolean() {
+        assert_round_trip(b"#t\r\n");
+        assert_round_trip(b"#f\r\n");
+    }
+
+    #[test]
+    pub fn test_serialize_map() {
+        assert_round_trip(b"%1\r\n+key\r\n+val\r\n");
+        assert_round_trip(b"%2\r\n$1\r\na\r\n*1\r\n:1\r\n+b\r\n%0\r\n");
+    }
+
+    #[test]
+    pub fn test_serialize_set() {
+        assert_round_trip(b"~2\r\n+a\r\n+b\r\n");
+        assert_round_trip(b"~0\r\n");
+    }
+
+    #[test]
+    pub fn test_serialize_big_number() {
+        assert_round_trip(b"(3492890328409238509324850943850943825024385\r\n");
+    }
+
+    #[test]
+    pub fn test_serialize_push() {
+        assert_round_trip(b">3\r\n$7\r\nmessage\r\n$2\r\nch\r\n$2\r\nhi\r\n");
+    }
+
+    #[test]
+    pub fn test_serialize_verbatim() {
+        assert_round_trip(b"=15\r\ntxt:Some string\r\n");
+        assert_round_trip(b"=4\r\nmkd:\r\n");
+    }
+
+    #[test]
+    pub fn test_serialize_nil() {
+        assert_round_trip(b"$-1\r\n");
+    }
+
+    #[test]
+    pub fn test_serialize_array() {
+        assert_round_trip(b"*2\r\n+one\r\n$3\r\ntwo\r\n");
+        assert_round_trip(b"*2\r\n*1\r\n:1\r\n$-1\r\n");
+        assert_round_trip(b"*0\r\n");
+    }
+
+    #[test]
+    pub fn test_serialize_segments() {
+        let large = Bytes::from(vec![b'x'; 4096]);
+        let frame = Frame::Array(vec![
+            Frame::Bulk(Bytes::from_static(b"SET")),
+            Frame::Bulk(Bytes::from_static(b"key")),
+            Frame::Bulk(large.clone()),
+        ]);
+
+        let mut buf = BytesMut::new();
+        let mut segments = Vec::new();
+        frame.serialize_segments(&mut buf, &mut segments);
+        segments.push(buf.freeze());
+
+        let mut expected = BytesMut::new();
+        frame.serialize(&mut expected);
+        assert_eq!(expected, segments.concat());
+        // the large payload is shared, not copied.
+        assert_eq!(3, segments.len());
+        assert_eq!(large.as_ptr(), segments[1].as_ptr());
+    }
+
+    #[test]
+    pub fn test_serialize_nested_array() {
+        let frame = Frame::Array(vec![
+            Frame::Bulk(Bytes::from_static(b"SET")),
+            Frame::Array(vec![Frame::Integer(1), Frame::Nil, Frame::Array(vec![])]),
+            Frame::Error(String::from("ERR")),
+        ]);
+
+        let mut buf = BytesMut::new();
+        frame.serialize(&mut buf);
+
+        assert_eq!(frame, Frame::parse(&mut Cursor::new(&buf[..])).unwrap());
+    }
+
+    #[test]
+    pub fn test_display_array() {
+        let frame = Frame::Array(vec![
+            Frame::Bulk(Bytes::from_static(b"foo")),
+            Frame::Integer(42),
+            Frame::Nil,
+            Frame::Array(vec![Frame::Simple(String::from("OK")), Frame::Error(String::from("ERR"))]),
+            Frame::Array(vec![]),
+        ]);
+
+        assert_eq!(
+            "1) \"foo\"\n2) (integer) 42\n3) (nil)\n4) 1) OK\n   2) (error) ERR\n5) (empty array)",
+            frame.to_string()
+        );
+    }
+
+    #[test]
+    pub fn test_display_bulk() {
+        assert_eq!("Hello world", Frame::Bulk(Bytes::from_static(b"Hello world")).to_string());
+        assert_eq!("(nil)", Frame::Nil.to_string());
+    }
+
+    fn frames() -> Vec<Frame> {
+        vec![
+            Frame::Simple(String::from("OK")),
+            Frame::Error(String::from("ERR")),
+            Frame::Integer(42),
+            Frame::Bulk(Bytes::from_static(b"bulk")),
+            Frame::Nil,
+            Frame::Array(vec![]),
+        ]
+    }
+
+    #[test]
+    pub fn test_as_str() {
+        let actual: Vec<_> = frames().iter().map(|frame| frame.as_str().map(String::from)).collect();
+
+        assert_eq!(vec![Some(String::from("OK")), None, None, Some(String::from("bulk")), None, None], actual);
+        assert_eq!(None, Frame::Bulk(Bytes::from_static(b"\xff")).as_str());
+    }
+
+    #[test]
+    pub fn test_as_bytes() {
+        let actual: Vec<_> = frames().iter().map(|frame| frame.as_bytes().map(<[u8]>::to_vec)).collect();
+
+        assert_eq!(vec![None, None, None, Some(b"bulk".to_vec()), None, None], actual);
+    }
+
+    #[test]
+    pub fn test_as_integer() {
+        let actual: Vec<_> = frames().iter().map(Frame::as_integer).collect();
+
+        assert_eq!(vec![None, None, Some(42), None, None, None], actual);
+    }
+
+    #[test]
+    pub fn test_as_bool() {
+        let actual: Vec<_> = frames().iter().map(Frame::as_bool).collect();
+
+        assert_eq!(vec![None; 6], actual);
+        assert_eq!(Some(true), Frame::Boolean(true).as_bool());
+        assert_eq!(Some(false), Frame::Boolean(false).as_bool());
+    }
+
+    #[test]
+    pub fn test_is_nil() {
+        let actual: Vec<_> = frames().iter().map(Frame::is_nil).collect();
+
+        assert_eq!(vec![false, false, false, false, true, false], actual);
+    }
 }
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+
+use crate::convert::pairs;
+use crate::{Connection, Result, ScanOptions, ScanStream};
+
+impl Connection {
+    pub async fn hget(&mut self, key: &str, field: &str) -> Result<Option<Bytes>> {
+        self.query(&[b"HGET", key.as_bytes(), field.as_bytes()]).await
+    }
+
+    /// Returns `true` when `field` is new, `false` when an existing value was overwritten.
+    pub async fn hset(&mut self, key: &str, field: &str, value: &[u8]) -> Result<bool> {
+        self.query(&[b"HSET", key.as_bytes(), field.as_bytes(), value]).await
+    }
+
+    /// Returns how many of `fields` existed and were removed.
+    pub async fn hdel(&mut self, key: &str, fields: &[&str]) -> Result<u64> {
+        let mut args = vec![b"HDEL" as &[u8], key.as_bytes()];
+        args.extend(fields.iter().map(|field| field.as_bytes()));
+        self.query(&args).await
+    }
+
+    /// The values of `fields` in the same order, `None` for a field that isn't set.
+    pub async fn hmget(&mut self, key: &str, fields: &[&str]) -> Result<Vec<Option<Bytes>>> {
+        let mut args = vec![b"HMGET" as &[u8], key.as_bytes()];
+        args.extend(fields.iter().map(|field| field.as_bytes()));
+        self.query(&args).await
+    }
+
+    /// Every field and value of the hash, read from a flat array under RESP2 and from a map under RESP3.
+    pub async fn hgetall(&mut self, key: &str) -> Result<Vec<(Bytes, Bytes)>> {
+        self.query(&[b"HGETALL", key.as_bytes()]).await
+    }
+
+    /// Like `hgetall`, keyed by field name. Fails on a field name that isn't UTF-8.
+    pub async fn hgetall_map(&mut self, key: &str) -> Result<HashMap<String, Bytes>> {
+        self.query(&[b"HGETALL", key.as_bytes()]).await
+    }
+
+    /// Walks the fields of a hash with `HSCAN`, yielding each field with its value.
+    pub fn hscan(&mut self, key: &str, opts: ScanOptions) -> ScanStream<'_, (Bytes, Bytes)> {
+        let command = vec![Bytes::from_static(b"HSCAN"), Bytes::copy_from_slice(key.as_bytes())];
+        self.scanner(command, opts, pairs)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::scan::test::collect;
+    use crate::test::{converse, mock};
+
+    #[tokio::test]
+    pub async fn test_hget() {
+        let mut conn = mock(b"*3\r\n$4\r\nHGET\r\n$4\r\nuser\r\n$4\r\nname\r\n", b"$5\r\nalice\r\n").await;
+        assert_eq!(Some(Bytes::from_static(b"alice")), conn.hget("user", "name").await.unwrap());
+
+        let mut conn = mock(b"*3\r\n$4\r\nHGET\r\n$4\r\nuser\r\n$3\r\nage\r\n", b"$-1\r\n").await;
+        assert_eq!(None, conn.hget("user", "age").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_hset() {
+        let mut conn = mock(b"*4\r\n$4\r\nHSET\r\n$4\r\nuser\r\n$4\r\nname\r\n$5\r\nalice\r\n", b":1\r\n").await;
+        assert!(conn.hset("user", "name", b"alice").await.unwrap());
+
+        let mut conn = mock(b"*4\r\n$4\r\nHSET\r\n$4\r\nuser\r\n$4\r\nname\r\n$3\r\nbob\r\n", b":0\r\n").await;
+        assert!(!conn.hset("user", "name", b"bob").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_hdel() {
+        let mut conn = mock(b"*4\r\n$4\r\nHDEL\r\n$4\r\nuser\r\n$4\r\nname\r\n$3\r\nage\r\n", b":1\r\n").await;
+
+        assert_eq!(1, conn.hdel("user", &["name", "age"]).await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_hmget() {
+        let mut conn =
+            mock(b"*4\r\n$5\r\nHMGET\r\n$4\r\nuser\r\n$4\r\nname\r\n$3\r\nage\r\n", b"*2\r\n$5\r\nalice\r\n$-1\r\n")
+                .await;
+
+        let values = conn.hmget("user", &["name", "age"]).await.unwrap();
+
+        assert_eq!(vec![Some(Bytes::from_static(b"alice")), None], values);
+    }
+
+    #[tokio::test]
+    pub async fn test_hgetall_resp2() {
+        let mut conn = mock(
+            b"*2\r\n$7\r\nHGETALL\r\n$4\r\nuser\r\n",
+            b"*4\r\n$4\r\nname\r\n$5\r\nalice\r\n$3\r\nage\r\n$2\r\n30\r\n",
+        )
+        .await;
+
+        let fields = conn.hgetall("user").await.unwrap();
+
+        assert_eq!(
+            vec![
+                (Bytes::from_static(b"name"), Bytes::from_static(b"alice")),
+                (Bytes::from_static(b"age"), Bytes::from_static(b"30")),
+            ],
+            fields
+        );
+    }
+
+    #[tokio::test]
+    pub async fn test_hgetall_resp3() {
+        let mut conn = mock(b"*2\r\n$7\r\nHGETALL\r\n$4\r\nuser\r\n", b"%1\r\n$4\r\nname\r\n$5\r\nalice\r\n").await;
+
+        let fields = conn.hgetall("user").await.unwrap();
+
+        assert_eq!(vec![(Bytes::from_static(b"name"), Bytes::from_static(b"alice"))], fields);
+    }
+
+    #[tokio::test]
+    pub async fn test_hgetall_map() {
+        let mut conn = mock(
+            b"*2\r\n$7\r\nHGETALL\r\n$4\r\nuser\r\n",
+            b"*4\r\n$4\r\nname\r\n$5\r\nalice\r\n$3\r\nage\r\n$2\r\n30\r\n",
+        )
+        .await;
+
+        let fields = conn.hgetall_map("user").await.unwrap();
+
+        assert_eq!(Some(&Bytes::from_static(b"alice")), fields.get("name"));
+        assert_eq!(Some(&Bytes::from_static(b"30")), fields.get("age"));
+        assert_eq!(2, fields.len());
+    }
+
+    #[tokio::test]
+    pub async fn test_hgetall_empty() {
+        let mut conn = mock(b"*2\r\n$7\r\nHGETALL\r\n$4\r\nnope\r\n", b"*0\r\n").await;
+        assert!(conn.hgetall("nope").await.unwrap().is_empty());
+
+        let mut conn = mock(b"*2\r\n$7\r\nHGETALL\r\n$4\r\nnope\r\n", b"%0\r\n").await;
+        assert!(conn.hgetall("nope").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    pub async fn test_hscan() {
+        let addr = converse(vec![(
+            b"*3\r\n$5\r\nHSCAN\r\n$4\r\nuser\r\n$1\r\n0\r\n",
+            b"*2\r\n$1\r\n0\r\n*4\r\n$4\r\nname\r\n$5\r\nalice\r\n$3\r\nage\r\n$2\r\n30\r\n",
+        )])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let fields = collect(conn.hscan("user", ScanOptions::new())).await;
+
+        assert_eq!(
+            vec![
+                (Bytes::from_static(b"name"), Bytes::from_static(b"alice")),
+                (Bytes::from_static(b"age"), Bytes::from_static(b"30")),
+            ],
+            fields
+        );
+    }
+
+    #[tokio::test]
+    pub async fn test_hscan_pages() {
+        let addr = converse(vec![
+            (
+                b"*3\r\n$5\r\nHSCAN\r\n$4\r\nuser\r\n$1\r\n0\r\n",
+                b"*2\r\n$1\r\n3\r\n*2\r\n$4\r\nname\r\n$5\r\nalice\r\n",
+            ),
+            (b"*3\r\n$5\r\nHSCAN\r\n$4\r\nuser\r\n$1\r\n3\r\n", b"*2\r\n$1\r\n0\r\n*2\r\n$3\r\nage\r\n$2\r\n30\r\n"),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let fields: HashMap<_, _> = collect(conn.hscan("user", ScanOptions::new())).await.into_iter().collect();
+
+        assert_eq!(
+            HashMap::from([
+                (Bytes::from_static(b"name"), Bytes::from_static(b"alice")),
+                (Bytes::from_static(b"age"), Bytes::from_static(b"30")),
+            ]),
+            fields
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use crate::frame::Error;
+use crate::{Connection, Result};
+
+/// What `TTL` says about a key, Redis encodes the first two cases as `-2` and `-1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TtlResult {
+    NoKey,
+    NoExpiry,
+    Seconds(u64),
+}
+
+impl Connection {
+    /// Returns `false` when the key doesn't exist.
+    pub async fn expire(&mut self, key: &str, seconds: u64) -> Result<bool> {
+        self.query(&[b"EXPIRE", key.as_bytes(), seconds.to_string().as_bytes()]).await
+    }
+
+    /// Like `expire`, with the timeout in milliseconds.
+    pub async fn pexpire(&mut self, key: &str, millis: u64) -> Result<bool> {
+        self.query(&[b"PEXPIRE", key.as_bytes(), millis.to_string().as_bytes()]).await
+    }
+
+    /// Returns `false` when the key doesn't exist or had no timeout to remove.
+    pub async fn persist(&mut self, key: &str) -> Result<bool> {
+        self.query(&[b"PERSIST", key.as_bytes()]).await
+    }
+
+    pub async fn ttl(&mut self, key: &str) -> Result<TtlResult> {
+        match self.query::<i64>(&[b"TTL", key.as_bytes()]).await? {
+            -2 => Ok(TtlResult::NoKey),
+            -1 => Ok(TtlResult::NoExpiry),
+            secs if secs >= 0 => Ok(TtlResult::Seconds(secs as u64)),
+            secs => Err(Error::due_to_protocol(format!("invalid ttl {secs}")).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::test::mock;
+
+    #[tokio::test]
+    pub async fn test_expire() {
+        let mut conn = mock(b"*3\r\n$6\r\nEXPIRE\r\n$3\r\nfoo\r\n$2\r\n10\r\n", b":1\r\n").await;
+
+        assert!(conn.expire("foo", 10).await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_expire_missing() {
+        let mut conn = mock(b"*3\r\n$6\r\nEXPIRE\r\n$3\r\nfoo\r\n$2\r\n10\r\n", b":0\r\n").await;
+
+        assert!(!conn.expire("foo", 10).await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_pexpire() {
+        let mut conn = mock(b"*3\r\n$7\r\nPEXPIRE\r\n$3\r\nfoo\r\n$4\r\n1500\r\n", b":1\r\n").await;
+
+        assert!(conn.pexpire("foo", 1500).await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_persist() {
+        let mut conn = mock(b"*2\r\n$7\r\nPERSIST\r\n$3\r\nfoo\r\n", b":0\r\n").await;
+
+        assert!(!conn.persist("foo").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_ttl() {
+        let mut conn = mock(b"*2\r\n$3\r\nTTL\r\n$3\r\nfoo\r\n", b":42\r\n").await;
+        assert_eq!(TtlResult::Seconds(42), conn.ttl("foo").await.unwrap());
+
+        let mut conn = mock(b"*2\r\n$3\r\nTTL\r\n$3\r\nfoo\r\n", b":-1\r\n").await;
+        assert_eq!(TtlResult::NoExpiry, conn.ttl("foo").await.unwrap());
+
+        let mut conn = mock(b"*2\r\n$3\r\nTTL\r\n$3\r\nfoo\r\n", b":-2\r\n").await;
+        assert_eq!(TtlResult::NoKey, conn.ttl("foo").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_ttl_invalid() {
+        let mut conn = mock(b"*2\r\n$3\r\nTTL\r\n$3\r\nfoo\r\n", b":-3\r\n").await;
+
+        assert!(conn.ttl("foo").await.is_err());
+    }
+}
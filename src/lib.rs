@@ -1,31 +1,1285 @@
 #![allow(dead_code)]
 
-use std::io;
+use std::io::{self, IoSlice};
+#[cfg(unix)]
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::time::Duration;
 
-use bytes::BytesMut;
-use tokio::io::BufWriter;
+use bytes::{Bytes, BytesMut};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufWriter, ReadBuf};
+#[cfg(unix)]
+use tokio::net::UnixStream;
 use tokio::net::{TcpStream, ToSocketAddrs};
+#[cfg(feature = "tls")]
+pub use tokio_rustls::rustls::{ClientConfig, RootCertStore};
 
-mod frame;
+pub use crate::cmd::{Cmd, ToArg};
+#[cfg(feature = "codec")]
+pub use crate::codec::FrameCodec;
+pub use crate::convert::FromFrame;
+use crate::frame::Error;
+pub use crate::frame::Frame;
+pub use crate::keys::TtlResult;
+pub use crate::manager::{Backoff, ConnectionManager};
+pub use crate::multiplexed::MultiplexedConnection;
+pub use crate::options::{Existence, ScanOptions, SetOptions, SetReply};
+pub use crate::pipeline::Pipeline;
+pub use crate::pool::{Pool, PoolConfig, PooledConnection};
+pub use crate::pubsub::{Message, Subscriber};
+pub use crate::scan::ScanStream;
+pub use crate::script::Script;
+use crate::stream::Stream;
+pub use crate::transaction::{Multi, Transaction};
+pub use crate::url::RedisUrl;
+
+mod cmd;
+#[cfg(feature = "codec")]
+mod codec;
+mod convert;
+pub mod frame;
+mod hash;
+mod keys;
+mod list;
+mod manager;
+mod multiplexed;
+mod options;
+mod pipeline;
+mod pool;
+mod pubsub;
+mod scan;
+mod script;
+mod set;
+mod stream;
+mod strings;
+mod transaction;
+mod url;
+mod zset;
+
+#[derive(Debug)]
+pub enum RedisError {
+    Io(io::Error),
+
+    Frame(Error),
+
+    ConnectionReset,
+
+    Timeout,
+
+    InvalidUrl(String),
+
+    InvalidArgument(String),
+
+    /// The connection dropped before the reply arrived, the command may or may not have run. A `ConnectionManager`
+    /// reconnects on the next command, so it can be retried.
+    Disconnected,
+
+    /// `EXEC` didn't run the transaction because a `WATCH`ed key changed, it's safe to retry.
+    TransactionAborted,
+}
+
+impl std::fmt::Display for RedisError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RedisError::Io(e) => write!(f, "io error, {e}"),
+            RedisError::Frame(e) => e.fmt(f),
+            RedisError::ConnectionReset => write!(f, "connection reset by peer"),
+            RedisError::Timeout => write!(f, "timed out waiting for the server"),
+            RedisError::InvalidUrl(e) => write!(f, "invalid url, {e}"),
+            RedisError::InvalidArgument(e) => write!(f, "invalid argument, {e}"),
+            RedisError::Disconnected => write!(f, "disconnected before the reply arrived"),
+            RedisError::TransactionAborted => write!(f, "transaction aborted, a watched key changed"),
+        }
+    }
+}
+
+impl std::error::Error for RedisError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RedisError::Io(e) => Some(e),
+            RedisError::Frame(e) => Some(e),
+            RedisError::ConnectionReset
+            | RedisError::Timeout
+            | RedisError::InvalidUrl(_)
+            | RedisError::InvalidArgument(_)
+            | RedisError::Disconnected
+            | RedisError::TransactionAborted => None,
+        }
+    }
+}
+
+impl From<io::Error> for RedisError {
+    fn from(e: io::Error) -> Self {
+        RedisError::Io(e)
+    }
+}
+
+impl From<Error> for RedisError {
+    fn from(e: Error) -> Self {
+        RedisError::Frame(e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, RedisError>;
+
+/// The RESP version spoken on a connection, every connection starts with RESP2.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProtocolVersion {
+    #[default]
+    Resp2,
+    Resp3,
+}
+
+impl ProtocolVersion {
+    fn version(&self) -> u8 {
+        match self {
+            ProtocolVersion::Resp2 => 2,
+            ProtocolVersion::Resp3 => 3,
+        }
+    }
+}
 
 pub struct Connection {
-    inner: BufWriter<TcpStream>,
+    inner: BufWriter<Stream>,
     buffer: BytesMut,
+    protocol: ProtocolVersion,
+    read_timeout: Option<Duration>,
+    db: u32,
+    /// Set once a read or write failed, the stream may be dead or stopped in the middle of a reply.
+    broken: bool,
+    /// Replies still owed for the frames written, above zero after a command was cancelled before its reply was
+    /// read.
+    pending: usize,
+    /// Whether keys are watched, the server drops the watches on the next `EXEC`, `DISCARD` or `UNWATCH`.
+    watching: bool,
 }
 
 impl Connection {
-    pub async fn connect<A>(addr: A) -> io::Result<Self>
+    pub async fn connect<A>(addr: A) -> Result<Self>
     where
         A: ToSocketAddrs,
     {
-        fn __new(stream: TcpStream) -> Connection {
-            let inner = BufWriter::new(stream);
-            let buffer = BytesMut::with_capacity(4 * 1024);
+        let stream = TcpStream::connect(addr).await?;
+        Ok(Connection::from_stream(stream))
+    }
+
+    /// Wraps a socket that was set up elsewhere, e.g. with custom socket options or through a proxy.
+    pub fn from_stream(stream: TcpStream) -> Connection {
+        Connection::new(Stream::Tcp(stream))
+    }
+
+    /// Connects as described by a `redis://` or `rediss://` url, then authenticates and selects the database it
+    /// names.
+    pub async fn connect_url(url: &str) -> Result<Self> {
+        Connection::connect_to(&RedisUrl::parse(url)?).await
+    }
 
-            Connection { inner, buffer }
+    pub(crate) async fn connect_to(url: &RedisUrl) -> Result<Self> {
+        let addr = (url.host.as_str(), url.port);
+
+        let mut conn = if url.tls {
+            #[cfg(feature = "tls")]
+            {
+                Connection::connect_tls(addr, &url.host, None).await?
+            }
+            #[cfg(not(feature = "tls"))]
+            {
+                return Err(RedisError::InvalidUrl(String::from("`rediss://` needs the `tls` feature")));
+            }
+        } else {
+            Connection::connect(addr).await?
+        };
+
+        if let Some(password) = &url.password {
+            conn.auth(url.username.as_deref(), password).await?;
+        }
+        if url.db != 0 {
+            conn.select(url.db).await?;
         }
+        Ok(conn)
+    }
+
+    /// Same as `connect_url`, handy when the url comes straight from an environment variable.
+    pub async fn open(url: &str) -> Result<Self> {
+        Connection::connect_url(url).await
+    }
+
+    /// Connects to a server listening on a Unix domain socket, e.g. `/var/run/redis/redis.sock`.
+    #[cfg(unix)]
+    pub async fn connect_unix<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let stream = UnixStream::connect(path).await?;
+        Ok(Connection::new(Stream::Unix(stream)))
+    }
+
+    /// Connects over TLS, `server_name` is sent for SNI and checked against the server's certificate. Without
+    /// `roots` the certificate has to chain up to one of the Mozilla root CAs.
+    #[cfg(feature = "tls")]
+    pub async fn connect_tls<A>(addr: A, server_name: &str, roots: Option<RootCertStore>) -> Result<Self>
+    where
+        A: ToSocketAddrs,
+    {
+        let roots = roots.unwrap_or_else(|| RootCertStore { roots: webpki_roots::TLS_SERVER_ROOTS.into() });
+        let config = ClientConfig::builder().with_root_certificates(roots).with_no_client_auth();
+        Connection::connect_tls_with_config(addr, server_name, std::sync::Arc::new(config)).await
+    }
+
+    /// Like `connect_tls`, but with a caller-built `ClientConfig`, e.g. for client certificates or a private CA.
+    #[cfg(feature = "tls")]
+    pub async fn connect_tls_with_config<A>(
+        addr: A,
+        server_name: &str,
+        config: std::sync::Arc<ClientConfig>,
+    ) -> Result<Self>
+    where
+        A: ToSocketAddrs,
+    {
+        use tokio_rustls::rustls::pki_types::ServerName;
+        use tokio_rustls::TlsConnector;
+
+        let domain =
+            ServerName::try_from(server_name.to_owned()).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
 
         let stream = TcpStream::connect(addr).await?;
-        Ok(__new(stream))
+        let stream = TlsConnector::from(config).connect(domain, stream).await?;
+        Ok(Connection::new(Stream::Tls(Box::new(stream))))
+    }
+
+    fn new(stream: Stream) -> Connection {
+        let inner = BufWriter::new(stream);
+        let buffer = BytesMut::with_capacity(4 * 1024);
+
+        Connection {
+            inner,
+            buffer,
+            protocol: ProtocolVersion::Resp2,
+            read_timeout: None,
+            db: 0,
+            broken: false,
+            pending: 0,
+            watching: false,
+        }
+    }
+
+    /// Like `connect`, but gives up with `RedisError::Timeout` when the server can't be reached within `timeout`.
+    pub async fn connect_timeout<A>(addr: A, timeout: Duration) -> Result<Self>
+    where
+        A: ToSocketAddrs,
+    {
+        match tokio::time::timeout(timeout, Connection::connect(addr)).await {
+            Ok(conn) => conn,
+            Err(_) => Err(RedisError::Timeout),
+        }
+    }
+
+    /// Connects and authenticates with `AUTH`, a `username` selects the ACL user instead of `default`.
+    pub async fn connect_with_auth<A>(addr: A, username: Option<&str>, password: &str) -> Result<Self>
+    where
+        A: ToSocketAddrs,
+    {
+        let mut conn = Connection::connect(addr).await?;
+        conn.auth(username, password).await?;
+        Ok(conn)
+    }
+
+    pub async fn read_frame(&mut self) -> Result<Option<Frame>> {
+        self.read_frame_within(self.read_timeout).await
+    }
+
+    /// Like `read_frame`, with `timeout` bounding each read instead of the connection's read timeout.
+    async fn read_frame_within(&mut self, timeout: Option<Duration>) -> Result<Option<Frame>> {
+        let frame = self.read_frame_inner(timeout).await;
+        match frame {
+            Ok(Some(_)) => self.pending = self.pending.saturating_sub(1),
+            _ => self.broken = true,
+        }
+        frame
+    }
+
+    async fn read_frame_inner(&mut self, timeout: Option<Duration>) -> Result<Option<Frame>> {
+        loop {
+            if let Some(frame) = Frame::decode(&mut self.buffer)? {
+                return Ok(Some(frame));
+            }
+
+            let read = self.inner.read_buf(&mut self.buffer);
+            let n = match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, read).await.map_err(|_| RedisError::Timeout)??,
+                None => read.await?,
+            };
+            if 0 == n {
+                return if self.buffer.is_empty() { Ok(None) } else { Err(Error::StreamEndedEarly.into()) };
+            }
+        }
+    }
+
+    /// Like `read_frame`, for poll-based code such as a `Stream` impl. The read timeout isn't applied.
+    pub(crate) fn poll_read_frame(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<Frame>>> {
+        let frame = ready!(self.poll_read_frame_inner(cx));
+        match frame {
+            Ok(Some(_)) => self.pending = self.pending.saturating_sub(1),
+            _ => self.broken = true,
+        }
+        Poll::Ready(frame)
+    }
+
+    fn poll_read_frame_inner(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<Frame>>> {
+        loop {
+            if let Some(frame) = Frame::decode(&mut self.buffer)? {
+                return Poll::Ready(Ok(Some(frame)));
+            }
+
+            // reads straight into the spare room at the end of the buffer, then cuts it back to what was read.
+            let filled = self.buffer.len();
+            self.buffer.resize(filled + 4 * 1024, 0);
+            let mut buf = ReadBuf::new(&mut self.buffer[filled..]);
+            let read = Pin::new(&mut self.inner).poll_read(cx, &mut buf);
+            let n = buf.filled().len();
+            self.buffer.truncate(filled + n);
+            ready!(read)?;
+
+            if 0 == n {
+                return Poll::Ready(if self.buffer.is_empty() {
+                    Ok(None)
+                } else {
+                    Err(Error::StreamEndedEarly.into())
+                });
+            }
+        }
+    }
+
+    pub async fn write_frame(&mut self, frame: &Frame) -> Result<()> {
+        let mut buf = BytesMut::new();
+        frame.serialize(&mut buf);
+        self.pending += 1;
+        self.write_all(&buf).await
+    }
+
+    /// Writes already serialized frames and flushes them.
+    pub(crate) async fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        let written = match self.inner.write_all(buf).await {
+            Ok(()) => self.inner.flush().await,
+            Err(e) => Err(e),
+        };
+        if written.is_err() {
+            self.broken = true;
+        }
+        Ok(written?)
+    }
+
+    /// Writes several frames at once. When the stream supports vectored writes, large bulk payloads are handed to it
+    /// as they are instead of being copied into one buffer with the rest.
+    pub(crate) async fn write_frames(&mut self, frames: &[Frame]) -> Result<()> {
+        self.pending += frames.len();
+        let mut buf = BytesMut::new();
+        if !self.inner.get_ref().is_write_vectored() {
+            for frame in frames {
+                frame.serialize(&mut buf);
+            }
+            return self.write_all(&buf).await;
+        }
+
+        let mut segments = Vec::new();
+        for frame in frames {
+            frame.serialize_segments(&mut buf, &mut segments);
+        }
+        segments.push(buf.freeze());
+
+        let mut slices: Vec<_> = segments.iter().map(|segment| IoSlice::new(segment)).collect();
+        let mut slices = &mut slices[..];
+        let written = async {
+            while !slices.is_empty() {
+                match self.inner.write_vectored(slices).await? {
+                    0 => return Err(io::ErrorKind::WriteZero.into()),
+                    n => IoSlice::advance_slices(&mut slices, n),
+                }
+            }
+            self.inner.flush().await
+        }
+        .await;
+        if written.is_err() {
+            self.broken = true;
+        }
+        Ok(written?)
+    }
+
+    /// Whether a read or write on this connection failed, after that it can't be trusted with another command.
+    pub(crate) fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    /// Whether a reply is still to be read, the next command would get it instead of its own.
+    pub(crate) fn has_pending(&self) -> bool {
+        self.pending > 0
+    }
+
+    pub(crate) fn is_watching(&self) -> bool {
+        self.watching
+    }
+
+    /// Bounds every single read from the socket, `None` waits for the server forever.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.read_timeout = timeout;
+    }
+
+    pub fn read_timeout(&self) -> Option<Duration> {
+        self.read_timeout
+    }
+
+    pub fn protocol(&self) -> ProtocolVersion {
+        self.protocol
+    }
+
+    /// Switches the connection to `protocol` with `HELLO`, servers older than Redis 6 reply with an error.
+    pub async fn handshake(&mut self, protocol: ProtocolVersion) -> Result<()> {
+        self.hello(protocol.version()).await?;
+        Ok(())
+    }
+
+    /// Sends `HELLO <version>` and returns the server's description of itself, the connection keeps speaking
+    /// whichever protocol the server settled on.
+    pub async fn hello(&mut self, version: u8) -> Result<Frame> {
+        let reply = self.send_command(&[b"HELLO", version.to_string().as_bytes()]).await?;
+        let proto = match &reply {
+            Frame::Map(_) => reply.get("proto").and_then(Frame::as_integer),
+            Frame::Array(fields) => fields
+                .chunks(2)
+                .find(|pair| pair[0].as_str() == Some("proto"))
+                .and_then(|pair| pair.get(1))
+                .and_then(Frame::as_integer),
+            _ => None,
+        };
+        self.protocol = match proto {
+            Some(2) => ProtocolVersion::Resp2,
+            Some(3) => ProtocolVersion::Resp3,
+            _ => return Err(Error::unexpected(&reply).into()),
+        };
+        Ok(reply)
+    }
+
+    /// The password never shows up in the returned error, only the server's reply does.
+    pub async fn auth(&mut self, username: Option<&str>, password: &str) -> Result<()> {
+        let reply = match username {
+            Some(username) => self.send_command(&[b"AUTH", username.as_bytes(), password.as_bytes()]).await?,
+            None => self.send_command(&[b"AUTH", password.as_bytes()]).await?,
+        };
+        match reply {
+            Frame::Simple(s) if s == "OK" => Ok(()),
+            frame => Err(Error::unexpected(&frame).into()),
+        }
+    }
+
+    /// Switches to the logical database `db`, the index is kept so it can be selected again after a reconnect.
+    pub async fn select(&mut self, db: u32) -> Result<()> {
+        match self.send_command(&[b"SELECT", db.to_string().as_bytes()]).await? {
+            Frame::Simple(s) if s == "OK" => {
+                self.db = db;
+                Ok(())
+            }
+            frame => Err(Error::unexpected(&frame).into()),
+        }
+    }
+
+    /// The logical database last switched to with `select`.
+    pub fn db(&self) -> u32 {
+        self.db
+    }
+
+    pub async fn ping(&mut self) -> Result<()> {
+        match self.send_command(&[b"PING"]).await? {
+            Frame::Simple(s) if s == "PONG" => Ok(()),
+            frame => Err(Error::unexpected(&frame).into()),
+        }
+    }
+
+    pub async fn ping_msg(&mut self, msg: &[u8]) -> Result<Bytes> {
+        match self.send_command(&[b"PING", msg]).await? {
+            Frame::Bulk(data) => Ok(data),
+            frame => Err(Error::unexpected(&frame).into()),
+        }
+    }
+
+    pub async fn echo(&mut self, msg: &[u8]) -> Result<Bytes> {
+        match self.send_command(&[b"ECHO", msg]).await? {
+            Frame::Bulk(data) => Ok(data),
+            frame => Err(Error::unexpected(&frame).into()),
+        }
+    }
+
+    pub async fn get<T>(&mut self, key: &str) -> Result<Option<T>>
+    where
+        T: FromFrame,
+    {
+        self.query(&[b"GET", key.as_bytes()]).await
+    }
+
+    pub async fn set(&mut self, key: &str, value: &[u8]) -> Result<()> {
+        match self.send_command(&[b"SET", key.as_bytes(), value]).await? {
+            Frame::Simple(s) if s == "OK" => Ok(()),
+            frame => Err(Error::unexpected(&frame).into()),
+        }
+    }
+
+    /// `SET` with an expiry, a condition or `GET`, see `SetReply` for what comes back.
+    pub async fn set_opts(&mut self, key: &str, value: &[u8], opts: SetOptions) -> Result<SetReply> {
+        let get = opts.get;
+        let opts = opts.to_args()?;
+        let mut args = vec![b"SET" as &[u8], key.as_bytes(), value];
+        args.extend(opts.iter().map(|arg| arg.as_bytes()));
+        match (self.send_command(&args).await?, get) {
+            (Frame::Nil, false) => Ok(SetReply::Skipped),
+            (Frame::Simple(s), false) if s == "OK" => Ok(SetReply::Written),
+            (Frame::Bulk(data), true) => Ok(SetReply::Previous(Some(data))),
+            (Frame::Nil, true) => Ok(SetReply::Previous(None)),
+            (frame, _) => Err(Error::unexpected(&frame).into()),
+        }
+    }
+
+    /// Sends a command and waits for its reply, an error reply from the server is returned as an `Err`.
+    pub async fn send_command(&mut self, args: &[&[u8]]) -> Result<Frame> {
+        match self.cmd(args).await? {
+            Frame::Error(e) => Err(Error::due_to_protocol(e).into()),
+            frame => Ok(frame),
+        }
+    }
+
+    /// Like `send_command`, converting the reply into `T`, e.g. `let n: i64 = conn.query(&["INCR", "counter"])`.
+    /// Takes anything that writes out as arguments, a `Cmd` included. A reply that doesn't convert is an `Err`, the
+    /// connection stays usable.
+    pub async fn query<T>(&mut self, args: impl ToArg) -> Result<T>
+    where
+        T: FromFrame,
+    {
+        self.query_within(args, self.read_timeout).await
+    }
+
+    /// Like `query`, with `timeout` bounding the reads for this command only. Nothing is left to restore when the
+    /// future is cancelled.
+    pub(crate) async fn query_within<T>(&mut self, args: impl ToArg, timeout: Option<Duration>) -> Result<T>
+    where
+        T: FromFrame,
+    {
+        let mut buf = Vec::new();
+        args.write_arg(&mut buf);
+
+        self.write_frame(&Frame::Array(buf.into_iter().map(Frame::Bulk).collect())).await?;
+        match self.read_reply_within(timeout).await? {
+            Frame::Error(e) => Err(Error::due_to_protocol(e).into()),
+            frame => Ok(T::from_frame(frame)?),
+        }
+    }
+
+    /// Sends an arbitrary command and returns the reply as it is, error replies included.
+    pub async fn cmd<I, S>(&mut self, args: I) -> Result<Frame>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[u8]>,
+    {
+        self.write_frame(&command(args)).await?;
+        self.read_reply().await
+    }
+
+    /// Reads the next frame, a connection closed before the reply arrived is an error.
+    async fn read_reply(&mut self) -> Result<Frame> {
+        self.read_reply_within(self.read_timeout).await
+    }
+
+    async fn read_reply_within(&mut self, timeout: Option<Duration>) -> Result<Frame> {
+        match self.read_frame_within(timeout).await? {
+            Some(frame) => Ok(frame),
+            None => Err(RedisError::ConnectionReset),
+        }
+    }
+}
+
+/// Encodes a command as an array of bulk strings, the form Redis expects requests in.
+fn command<I, S>(args: I) -> Frame
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<[u8]>,
+{
+    Frame::Array(args.into_iter().map(|arg| Frame::Bulk(Bytes::copy_from_slice(arg.as_ref()))).collect())
+}
+
+#[cfg(test)]
+pub mod test {
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// Accepts one connection, asserts it sends `request` and answers with `reply`.
+    async fn listen(request: &'static [u8], reply: &'static [u8]) -> std::net::SocketAddr {
+        converse(vec![(request, reply)]).await
+    }
+
+    /// Like `listen`, but answers every request in turn before reading the next one.
+    pub(crate) async fn converse(steps: Vec<(&'static [u8], &'static [u8])>) -> std::net::SocketAddr {
+        converse_many(vec![steps]).await
+    }
+
+    /// Like `converse`, accepting one connection per conversation and closing it once the conversation is over.
+    pub(crate) async fn converse_many(conversations: Vec<Vec<(&'static [u8], &'static [u8])>>) -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            for steps in conversations {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                for (request, reply) in steps {
+                    let mut buf = vec![0; request.len()];
+                    socket.read_exact(&mut buf).await.unwrap();
+                    assert_eq!(request, &buf[..]);
+                    socket.write_all(reply).await.unwrap();
+                }
+            }
+        });
+
+        addr
+    }
+
+    /// Polls `stream` for its next item, a stand-in for `StreamExt::next`.
+    pub(crate) async fn next<S>(stream: &mut S) -> Option<S::Item>
+    where
+        S: futures_core::Stream + Unpin,
+    {
+        std::future::poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+    }
+
+    pub(crate) async fn mock(request: &'static [u8], reply: &'static [u8]) -> Connection {
+        Connection::connect(listen(request, reply).await).await.unwrap()
+    }
+
+    async fn serve(chunks: &'static [&'static [u8]]) -> Connection {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            for chunk in chunks {
+                socket.write_all(chunk).await.unwrap();
+                socket.flush().await.unwrap();
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        });
+
+        Connection::connect(addr).await.unwrap()
+    }
+
+    #[tokio::test]
+    pub async fn test_read_frame_across_writes() {
+        let mut conn = serve(&[b"+PO", b"NG\r\n"]).await;
+
+        let frame = conn.read_frame().await.unwrap();
+
+        assert_eq!(Some(Frame::Simple(String::from("PONG"))), frame);
+    }
+
+    #[tokio::test]
+    pub async fn test_poll_read_frame_across_writes() {
+        let mut conn = serve(&[b"+PO", b"NG\r\n:1\r\n"]).await;
+
+        let frame = std::future::poll_fn(|cx| conn.poll_read_frame(cx)).await.unwrap();
+        assert_eq!(Some(Frame::Simple(String::from("PONG"))), frame);
+        // the spare room read into is cut off again, only the unread frame is left.
+        assert_eq!(b":1\r\n" as &[u8], &conn.buffer[..]);
+    }
+
+    #[tokio::test]
+    pub async fn test_read_frame_compacts_buffer() {
+        let mut conn = serve(&[b"+OK\r\n:1\r\n"]).await;
+
+        assert_eq!(Some(Frame::Simple(String::from("OK"))), conn.read_frame().await.unwrap());
+        // the second frame arrived with the first one and is kept as it is.
+        assert_eq!(b":1\r\n" as &[u8], &conn.buffer[..]);
+        assert_eq!(Some(Frame::Integer(1)), conn.read_frame().await.unwrap());
+        assert!(conn.buffer.is_empty());
+    }
+
+    #[tokio::test]
+    pub async fn test_read_frame_closed() {
+        let mut conn = serve(&[b"+PONG\r\n"]).await;
+
+        assert_eq!(Some(Frame::Simple(String::from("PONG"))), conn.read_frame().await.unwrap());
+        assert_eq!(None, conn.read_frame().await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_read_frame_reset() {
+        let mut conn = serve(&[b"$11\r\nHello"]).await;
+
+        let err = conn.read_frame().await.unwrap_err();
+
+        assert!(matches!(err, RedisError::Frame(Error::StreamEndedEarly)));
+    }
+
+    #[tokio::test]
+    pub async fn test_read_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 14];
+            socket.read_exact(&mut buf).await.unwrap();
+            // accepts the command but never replies.
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let mut conn = Connection::connect(addr).await.unwrap();
+        conn.set_read_timeout(Some(Duration::from_millis(50)));
+
+        assert!(matches!(conn.ping().await, Err(RedisError::Timeout)));
+    }
+
+    #[tokio::test]
+    pub async fn test_read_timeout_per_read() {
+        let mut conn = serve(&[b"+", b"P", b"O", b"N", b"G", b"\r\n"]).await;
+        // the whole frame takes longer than the timeout, but no single read does.
+        conn.set_read_timeout(Some(Duration::from_millis(40)));
+
+        assert_eq!(Some(Frame::Simple(String::from("PONG"))), conn.read_frame().await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_read_frame_peer_closed_after_frame() {
+        let (client, mut server) = tokio::io::duplex(64);
+        let mut conn = Connection::new(Stream::Duplex(client));
+
+        server.write_all(b"+OK\r\n").await.unwrap();
+        drop(server);
+
+        assert_eq!(Some(Frame::Simple(String::from("OK"))), conn.read_frame().await.unwrap());
+        assert_eq!(None, conn.read_frame().await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_read_frame_peer_closed_mid_frame() {
+        let (client, mut server) = tokio::io::duplex(64);
+        let mut conn = Connection::new(Stream::Duplex(client));
+
+        server.write_all(b"*2\r\n$3\r\nfoo\r\n$3\r\nba").await.unwrap();
+        drop(server);
+
+        assert!(matches!(conn.read_frame().await, Err(RedisError::Frame(Error::StreamEndedEarly))));
+    }
+
+    #[tokio::test]
+    pub async fn test_read_reply_peer_closed() {
+        let (client, server) = tokio::io::duplex(64);
+        let mut conn = Connection::new(Stream::Duplex(client));
+        drop(server);
+
+        assert!(matches!(conn.read_reply().await, Err(RedisError::ConnectionReset)));
+    }
+
+    #[tokio::test]
+    pub async fn test_send_command() {
+        let mut conn = mock(b"*1\r\n$4\r\nPING\r\n", b"+PONG\r\n").await;
+
+        let frame = conn.send_command(&[b"PING"]).await.unwrap();
+
+        assert_eq!(Frame::Simple(String::from("PONG")), frame);
+    }
+
+    #[tokio::test]
+    pub async fn test_cmd() {
+        let mut conn = mock(b"*2\r\n$4\r\nECHO\r\n$2\r\nhi\r\n", b"$2\r\nhi\r\n").await;
+
+        let frame = conn.cmd(["ECHO", "hi"]).await.unwrap();
+
+        assert_eq!(Frame::Bulk(Bytes::from_static(b"hi")), frame);
+    }
+
+    #[tokio::test]
+    pub async fn test_cmd_error_reply() {
+        let mut conn = mock(b"*1\r\n$4\r\nNOPE\r\n", b"-ERR unknown command 'NOPE'\r\n").await;
+
+        let frame = conn.cmd(["NOPE"]).await.unwrap();
+
+        assert_eq!(Frame::Error(String::from("ERR unknown command 'NOPE'")), frame);
+    }
+
+    #[tokio::test]
+    pub async fn test_query() {
+        let addr = converse(vec![
+            (b"*2\r\n$4\r\nINCR\r\n$7\r\ncounter\r\n", b":3\r\n"),
+            (b"*2\r\n$3\r\nGET\r\n$7\r\ncounter\r\n", b"$1\r\n3\r\n"),
+            (b"*2\r\n$4\r\nTYPE\r\n$7\r\ncounter\r\n", b"+string\r\n"),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let n: i64 = conn.query(&["INCR", "counter"]).await.unwrap();
+        assert_eq!(3, n);
+        let value: Option<u64> = conn.query(&["GET", "counter"]).await.unwrap();
+        assert_eq!(Some(3), value);
+        // the reply made it off the wire, it just isn't a list.
+        let err = conn.query::<Vec<String>>(&["TYPE", "counter"]).await.unwrap_err();
+        assert!(matches!(err, RedisError::Frame(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    pub async fn test_auth_legacy() {
+        let mut conn = mock(b"*2\r\n$4\r\nAUTH\r\n$6\r\nsecret\r\n", b"+OK\r\n").await;
+
+        conn.auth(None, "secret").await.unwrap();
+    }
+
+    #[tokio::test]
+    pub async fn test_auth_acl() {
+        let mut conn = mock(b"*3\r\n$4\r\nAUTH\r\n$5\r\nalice\r\n$6\r\nsecret\r\n", b"+OK\r\n").await;
+
+        conn.auth(Some("alice"), "secret").await.unwrap();
+    }
+
+    #[tokio::test]
+    pub async fn test_auth_no_password_configured() {
+        let mut conn = mock(
+            b"*2\r\n$4\r\nAUTH\r\n$6\r\nsecret\r\n",
+            b"-ERR AUTH <password> called without any password configured for the default user.\r\n",
+        )
+        .await;
+
+        let err = conn.auth(None, "secret").await.unwrap_err();
+
+        assert!(matches!(&err, RedisError::Frame(Error::Protocol(e)) if e.starts_with("ERR AUTH")));
+        assert!(!err.to_string().contains("secret"));
+    }
+
+    #[tokio::test]
+    pub async fn test_select() {
+        let mut conn = mock(b"*2\r\n$6\r\nSELECT\r\n$1\r\n2\r\n", b"+OK\r\n").await;
+        assert_eq!(0, conn.db());
+
+        conn.select(2).await.unwrap();
+
+        assert_eq!(2, conn.db());
+    }
+
+    #[tokio::test]
+    pub async fn test_select_keeps_last_good_index() {
+        let addr = converse(vec![
+            (b"*2\r\n$6\r\nSELECT\r\n$1\r\n3\r\n", b"+OK\r\n"),
+            (b"*2\r\n$6\r\nSELECT\r\n$2\r\n16\r\n", b"-ERR DB index is out of range\r\n"),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        conn.select(3).await.unwrap();
+        assert!(conn.select(16).await.is_err());
+
+        assert_eq!(3, conn.db());
+    }
+
+    #[tokio::test]
+    pub async fn test_select_out_of_range() {
+        let mut conn = mock(b"*2\r\n$6\r\nSELECT\r\n$2\r\n99\r\n", b"-ERR DB index is out of range\r\n").await;
+
+        assert!(matches!(conn.select(99).await, Err(RedisError::Frame(Error::Protocol(_)))));
+        assert_eq!(0, conn.db());
+    }
+
+    #[tokio::test]
+    pub async fn test_ping() {
+        let mut conn = mock(b"*1\r\n$4\r\nPING\r\n", b"+PONG\r\n").await;
+
+        conn.ping().await.unwrap();
+    }
+
+    #[tokio::test]
+    pub async fn test_ping_error() {
+        let mut conn = mock(b"*1\r\n$4\r\nPING\r\n", b"-NOAUTH Authentication required.\r\n").await;
+
+        assert!(matches!(conn.ping().await, Err(RedisError::Frame(Error::Protocol(_)))));
+    }
+
+    #[tokio::test]
+    pub async fn test_ping_msg() {
+        let mut conn = mock(b"*2\r\n$4\r\nPING\r\n$5\r\nhello\r\n", b"$5\r\nhello\r\n").await;
+
+        assert_eq!(Bytes::from_static(b"hello"), conn.ping_msg(b"hello").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_ping_reply_kinds() {
+        // a bare `PING` is answered with a simple string, `PING <msg>` with a bulk one.
+        let mut conn = mock(b"*1\r\n$4\r\nPING\r\n", b"$4\r\nPONG\r\n").await;
+        assert!(conn.ping().await.is_err());
+
+        let mut conn = mock(b"*2\r\n$4\r\nPING\r\n$5\r\nhello\r\n", b"+hello\r\n").await;
+        assert!(conn.ping_msg(b"hello").await.is_err());
+    }
+
+    #[tokio::test]
+    pub async fn test_echo() {
+        let mut conn = mock(b"*2\r\n$4\r\nECHO\r\n$5\r\nhello\r\n", b"$5\r\nhello\r\n").await;
+
+        assert_eq!(Bytes::from_static(b"hello"), conn.echo(b"hello").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_get() {
+        let mut conn = mock(b"*2\r\n$3\r\nGET\r\n$3\r\nfoo\r\n", b"$3\r\nbar\r\n").await;
+
+        assert_eq!(Some(Bytes::from_static(b"bar")), conn.get("foo").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_get_missing() {
+        let mut conn = mock(b"*2\r\n$3\r\nGET\r\n$3\r\nfoo\r\n", b"$-1\r\n").await;
+
+        assert_eq!(None, conn.get::<Bytes>("foo").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_get_wrong_type() {
+        let mut conn = mock(
+            b"*2\r\n$3\r\nGET\r\n$4\r\nlist\r\n",
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n",
+        )
+        .await;
+
+        let err = conn.get::<Bytes>("list").await.unwrap_err();
+
+        assert!(matches!(err, RedisError::Frame(Error::Protocol(e)) if e.starts_with("WRONGTYPE")));
+    }
+
+    #[tokio::test]
+    pub async fn test_set_error() {
+        let mut conn = mock(
+            b"*3\r\n$3\r\nSET\r\n$3\r\nfoo\r\n$3\r\nbar\r\n",
+            b"-READONLY You can't write against a read only replica.\r\n",
+        )
+        .await;
+
+        assert!(matches!(conn.set("foo", b"bar").await, Err(RedisError::Frame(Error::Protocol(_)))));
+    }
+
+    #[tokio::test]
+    pub async fn test_set() {
+        let mut conn = mock(b"*3\r\n$3\r\nSET\r\n$3\r\nfoo\r\n$3\r\nbar\r\n", b"+OK\r\n").await;
+
+        conn.set("foo", b"bar").await.unwrap();
+    }
+
+    #[tokio::test]
+    pub async fn test_set_opts() {
+        let mut conn =
+            mock(b"*6\r\n$3\r\nSET\r\n$3\r\nfoo\r\n$3\r\nbar\r\n$2\r\nEX\r\n$2\r\n60\r\n$2\r\nNX\r\n", b"+OK\r\n")
+                .await;
+        let opts = SetOptions {
+            ex: Some(Duration::from_secs(60)),
+            if_exists: Some(Existence::NotExists),
+            ..Default::default()
+        };
+
+        assert_eq!(SetReply::Written, conn.set_opts("foo", b"bar", opts).await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_set_opts_skipped() {
+        let mut conn = mock(b"*4\r\n$3\r\nSET\r\n$3\r\nfoo\r\n$3\r\nbar\r\n$2\r\nXX\r\n", b"$-1\r\n").await;
+        let opts = SetOptions::new().xx();
+
+        assert_eq!(SetReply::Skipped, conn.set_opts("foo", b"bar", opts).await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_set_opts_get() {
+        let mut conn =
+            mock(b"*5\r\n$3\r\nSET\r\n$3\r\nfoo\r\n$3\r\nbar\r\n$7\r\nKEEPTTL\r\n$3\r\nGET\r\n", b"$3\r\nold\r\n")
+                .await;
+        let opts = SetOptions::new().keepttl().get();
+
+        let previous = conn.set_opts("foo", b"bar", opts).await.unwrap();
+
+        assert_eq!(SetReply::Previous(Some(Bytes::from_static(b"old"))), previous);
+    }
+
+    #[tokio::test]
+    pub async fn test_set_opts_get_missing() {
+        let mut conn = mock(b"*4\r\n$3\r\nSET\r\n$3\r\nfoo\r\n$3\r\nbar\r\n$3\r\nGET\r\n", b"$-1\r\n").await;
+
+        assert_eq!(SetReply::Previous(None), conn.set_opts("foo", b"bar", SetOptions::new().get()).await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_set_opts_conflict() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut conn = Connection::connect(listener.local_addr().unwrap()).await.unwrap();
+        let opts = SetOptions::new().px(Duration::from_millis(100)).keepttl();
+
+        assert!(matches!(conn.set_opts("foo", b"bar", opts).await, Err(RedisError::InvalidArgument(_))));
+    }
+
+    #[tokio::test]
+    pub async fn test_get_string() {
+        let mut conn = mock(b"*2\r\n$3\r\nGET\r\n$3\r\nfoo\r\n", b"$3\r\nbar\r\n").await;
+
+        assert_eq!(Some(String::from("bar")), conn.get::<String>("foo").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_handshake() {
+        let mut conn = mock(
+            b"*2\r\n$5\r\nHELLO\r\n$1\r\n3\r\n",
+            b"%3\r\n$6\r\nserver\r\n$5\r\nredis\r\n$7\r\nversion\r\n$5\r\n7.2.0\r\n$5\r\nproto\r\n:3\r\n",
+        )
+        .await;
+        assert_eq!(ProtocolVersion::Resp2, conn.protocol());
+
+        conn.handshake(ProtocolVersion::Resp3).await.unwrap();
+
+        assert_eq!(ProtocolVersion::Resp3, conn.protocol());
+    }
+
+    #[tokio::test]
+    pub async fn test_hello_resp2() {
+        let mut conn =
+            mock(b"*2\r\n$5\r\nHELLO\r\n$1\r\n2\r\n", b"*4\r\n$6\r\nserver\r\n$5\r\nredis\r\n$5\r\nproto\r\n:2\r\n")
+                .await;
+
+        let reply = conn.hello(2).await.unwrap();
+
+        assert_eq!(ProtocolVersion::Resp2, conn.protocol());
+        assert!(matches!(reply, Frame::Array(fields) if fields.len() == 4));
+    }
+
+    #[tokio::test]
+    pub async fn test_hello_unsupported() {
+        let mut conn = mock(
+            b"*2\r\n$5\r\nHELLO\r\n$1\r\n3\r\n",
+            b"-ERR unknown command `HELLO`, with args beginning with: `3`, \r\n",
+        )
+        .await;
+
+        let err = conn.hello(3).await.unwrap_err();
+
+        assert!(matches!(err, RedisError::Frame(Error::Protocol(e)) if e.starts_with("ERR unknown command")));
+    }
+
+    #[tokio::test]
+    pub async fn test_handshake_unsupported() {
+        let mut conn = mock(
+            b"*2\r\n$5\r\nHELLO\r\n$1\r\n3\r\n",
+            b"-ERR unknown command `HELLO`, with args beginning with: `3`, \r\n",
+        )
+        .await;
+
+        assert!(conn.handshake(ProtocolVersion::Resp3).await.is_err());
+        assert_eq!(ProtocolVersion::Resp2, conn.protocol());
+    }
+
+    #[tokio::test]
+    pub async fn test_connect_with_auth() {
+        let addr = listen(b"*2\r\n$4\r\nAUTH\r\n$6\r\nsecret\r\n", b"+OK\r\n").await;
+
+        Connection::connect_with_auth(addr, None, "secret").await.unwrap();
+    }
+
+    #[tokio::test]
+    pub async fn test_connect_with_auth_acl_user() {
+        let addr = listen(b"*3\r\n$4\r\nAUTH\r\n$5\r\nalice\r\n$6\r\nsecret\r\n", b"+OK\r\n").await;
+
+        Connection::connect_with_auth(addr, Some("alice"), "secret").await.unwrap();
+    }
+
+    #[tokio::test]
+    pub async fn test_connect_with_auth_rejected() {
+        let addr = listen(
+            b"*3\r\n$4\r\nAUTH\r\n$5\r\nalice\r\n$6\r\nsecret\r\n",
+            b"-WRONGPASS invalid username-password pair or user is disabled.\r\n",
+        )
+        .await;
+
+        let err = Connection::connect_with_auth(addr, Some("alice"), "secret").await.err().unwrap();
+
+        assert!(err.to_string().contains("WRONGPASS"));
+        assert!(!err.to_string().contains("secret"));
+        assert!(!format!("{err:?}").contains("secret"));
+    }
+
+    /// Accepts one TLS connection with the certificate in `testdata` and answers a single `PING`.
+    #[cfg(feature = "tls")]
+    async fn tls_listen() -> (std::net::SocketAddr, RootCertStore) {
+        use std::sync::Arc;
+
+        use tokio_rustls::rustls::pki_types::pem::PemObject;
+        use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+        use tokio_rustls::rustls::ServerConfig;
+        use tokio_rustls::TlsAcceptor;
+
+        let cert = CertificateDer::from_pem_slice(include_bytes!("../testdata/localhost.crt")).unwrap();
+        let key = PrivateKeyDer::from_pem_slice(include_bytes!("../testdata/localhost.key")).unwrap();
+        let config = ServerConfig::builder().with_no_client_auth().with_single_cert(vec![cert.clone()], key).unwrap();
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut socket = acceptor.accept(socket).await.unwrap();
+            let mut buf = [0; 14];
+            socket.read_exact(&mut buf).await.unwrap();
+            assert_eq!(b"*1\r\n$4\r\nPING\r\n", &buf);
+            socket.write_all(b"+PONG\r\n").await.unwrap();
+            socket.flush().await.unwrap();
+        });
+
+        let mut roots = RootCertStore::empty();
+        roots.add(cert).unwrap();
+        (addr, roots)
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    pub async fn test_connect_tls() {
+        let (addr, roots) = tls_listen().await;
+
+        let mut conn = Connection::connect_tls(addr, "localhost", Some(roots)).await.unwrap();
+
+        conn.ping().await.unwrap();
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    pub async fn test_connect_tls_with_config() {
+        let (addr, roots) = tls_listen().await;
+        let config = ClientConfig::builder().with_root_certificates(roots).with_no_client_auth();
+
+        let mut conn =
+            Connection::connect_tls_with_config(addr, "localhost", std::sync::Arc::new(config)).await.unwrap();
+
+        conn.ping().await.unwrap();
+    }
+
+    #[tokio::test]
+    pub async fn test_open() {
+        let addr = listen(b"*2\r\n$6\r\nSELECT\r\n$1\r\n5\r\n", b"+OK\r\n").await;
+
+        let conn = Connection::open(&format!("redis://{addr}/5")).await.unwrap();
+
+        assert_eq!(5, conn.db());
+    }
+
+    #[tokio::test]
+    pub async fn test_open_malformed() {
+        let err = Connection::open("redis://:secret@localhost:63790000").await.err().unwrap();
+
+        assert!(matches!(&err, RedisError::InvalidUrl(e) if e.contains("invalid port")));
+        assert!(!err.to_string().contains("secret"));
+    }
+
+    #[tokio::test]
+    pub async fn test_from_stream() {
+        let addr = listen(b"*1\r\n$4\r\nPING\r\n", b"+PONG\r\n").await;
+        let stream = TcpStream::connect(addr).await.unwrap();
+        stream.set_nodelay(true).unwrap();
+
+        let mut conn = Connection::from_stream(stream);
+
+        conn.ping().await.unwrap();
+    }
+
+    #[tokio::test]
+    pub async fn test_connect_url() {
+        let addr = converse(vec![
+            (b"*3\r\n$4\r\nAUTH\r\n$5\r\nalice\r\n$4\r\np@ss\r\n", b"+OK\r\n"),
+            (b"*2\r\n$6\r\nSELECT\r\n$1\r\n2\r\n", b"+OK\r\n"),
+        ])
+        .await;
+
+        let conn = Connection::connect_url(&format!("redis://alice:p%40ss@{addr}/2")).await.unwrap();
+
+        assert_eq!(2, conn.db());
+    }
+
+    #[tokio::test]
+    pub async fn test_connect_url_plain() {
+        let addr = listen(b"*1\r\n$4\r\nPING\r\n", b"+PONG\r\n").await;
+
+        let mut conn = Connection::connect_url(&format!("redis://{addr}")).await.unwrap();
+
+        conn.ping().await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    pub async fn test_connect_unix() {
+        use tokio::net::UnixListener;
+
+        let path = std::env::temp_dir().join(format!("async-redis-{}-connect-unix.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 14];
+            socket.read_exact(&mut buf).await.unwrap();
+            assert_eq!(b"*1\r\n$4\r\nPING\r\n", &buf);
+            socket.write_all(b"+PONG\r\n").await.unwrap();
+        });
+
+        let mut conn = Connection::connect_unix(&path).await.unwrap();
+        conn.ping().await.unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    pub async fn test_connect_unix_commands() {
+        use tokio::net::UnixListener;
+
+        let path = std::env::temp_dir().join(format!("async-redis-{}-unix-commands.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 31];
+            socket.read_exact(&mut buf).await.unwrap();
+            assert_eq!(b"*3\r\n$3\r\nSET\r\n$3\r\nfoo\r\n$3\r\nbar\r\n", &buf);
+            socket.write_all(b"+OK\r\n").await.unwrap();
+            let mut buf = [0; 22];
+            socket.read_exact(&mut buf).await.unwrap();
+            assert_eq!(b"*2\r\n$3\r\nGET\r\n$3\r\nfoo\r\n", &buf);
+            socket.write_all(b"$3\r\nbar\r\n").await.unwrap();
+        });
+
+        let mut conn = Connection::connect_unix(&path).await.unwrap();
+        conn.set("foo", b"bar").await.unwrap();
+        assert_eq!(Some(String::from("bar")), conn.get::<String>("foo").await.unwrap());
+        assert_eq!(None, conn.read_frame().await.unwrap());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    pub async fn test_connect_unix_missing() {
+        let path = std::env::temp_dir().join(format!("async-redis-{}-missing.sock", std::process::id()));
+
+        let result = Connection::connect_unix(&path).await;
+
+        assert!(matches!(result, Err(RedisError::Io(e)) if e.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[tokio::test]
+    pub async fn test_connect_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        assert!(Connection::connect_timeout(addr, Duration::from_secs(1)).await.is_ok());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a network that silently drops packets to 10.255.255.1"]
+    pub async fn test_connect_timeout_elapsed() {
+        let result = Connection::connect_timeout("10.255.255.1:6379", Duration::from_millis(50)).await;
+        assert!(matches!(result, Err(RedisError::Timeout)));
     }
 }
@@ -0,0 +1,256 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use crate::{Connection, Result};
+
+/// How much longer than a blocking command's own timeout a read may take before the read timeout fires.
+const BLOCKING_SLACK: Duration = Duration::from_secs(1);
+
+impl Connection {
+    /// Pushes `values` onto the head of the list one after another, returns the new length.
+    pub async fn lpush(&mut self, key: &str, values: &[&[u8]]) -> Result<u64> {
+        let mut args = vec![b"LPUSH" as &[u8], key.as_bytes()];
+        args.extend_from_slice(values);
+        self.query(&args).await
+    }
+
+    /// Appends `values` to the tail of the list, returns the new length.
+    pub async fn rpush(&mut self, key: &str, values: &[&[u8]]) -> Result<u64> {
+        let mut args = vec![b"RPUSH" as &[u8], key.as_bytes()];
+        args.extend_from_slice(values);
+        self.query(&args).await
+    }
+
+    /// `None` when the list is empty or doesn't exist.
+    pub async fn lpop(&mut self, key: &str) -> Result<Option<Bytes>> {
+        self.query(&[b"LPOP", key.as_bytes()]).await
+    }
+
+    pub async fn rpop(&mut self, key: &str) -> Result<Option<Bytes>> {
+        self.query(&[b"RPOP", key.as_bytes()]).await
+    }
+
+    /// Pops up to `count` elements from the head, fewer when the list is shorter and none when it doesn't exist.
+    pub async fn lpop_count(&mut self, key: &str, count: usize) -> Result<Vec<Bytes>> {
+        self.query(&[b"LPOP", key.as_bytes(), count.to_string().as_bytes()]).await
+    }
+
+    /// Like `lpop_count`, popping from the tail.
+    pub async fn rpop_count(&mut self, key: &str, count: usize) -> Result<Vec<Bytes>> {
+        self.query(&[b"RPOP", key.as_bytes(), count.to_string().as_bytes()]).await
+    }
+
+    pub async fn llen(&mut self, key: &str) -> Result<u64> {
+        self.query(&[b"LLEN", key.as_bytes()]).await
+    }
+
+    /// Pops from the head of the first non-empty list among `keys`, waiting up to `timeout` for one to get an
+    /// element, a zero `timeout` waits forever. Returns the key popped from and the value, `None` when it timed out.
+    ///
+    /// The read timeout set with `set_read_timeout` is stretched to cover `timeout` for this call only, and lifted
+    /// entirely when `timeout` is zero, so it doesn't fire while the server is legitimately blocking. The
+    /// connection's own setting is never touched, cancelling the call leaves it as it was.
+    pub async fn blpop(&mut self, keys: &[&str], timeout: Duration) -> Result<Option<(String, Bytes)>> {
+        self.blocking_pop(b"BLPOP", keys, timeout).await
+    }
+
+    /// Like `blpop`, popping from the tail.
+    pub async fn brpop(&mut self, keys: &[&str], timeout: Duration) -> Result<Option<(String, Bytes)>> {
+        self.blocking_pop(b"BRPOP", keys, timeout).await
+    }
+
+    async fn blocking_pop(&mut self, cmd: &[u8], keys: &[&str], timeout: Duration) -> Result<Option<(String, Bytes)>> {
+        let secs = timeout.as_secs_f64().to_string();
+        let mut args = vec![cmd];
+        args.extend(keys.iter().map(|key| key.as_bytes()));
+        args.push(secs.as_bytes());
+
+        let read_timeout = match self.read_timeout {
+            _ if timeout.is_zero() => None,
+            Some(read_timeout) => Some(read_timeout.max(timeout + BLOCKING_SLACK)),
+            None => None,
+        };
+        self.query_within(&args, read_timeout).await
+    }
+
+    /// The elements from `start` to `stop` inclusive, negative indices count from the tail.
+    pub async fn lrange(&mut self, key: &str, start: i64, stop: i64) -> Result<Vec<Bytes>> {
+        self.query(&[b"LRANGE", key.as_bytes(), start.to_string().as_bytes(), stop.to_string().as_bytes()]).await
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::test::{converse, mock};
+
+    #[tokio::test]
+    pub async fn test_push_range() {
+        let addr = converse(vec![
+            (b"*4\r\n$5\r\nRPUSH\r\n$4\r\njobs\r\n$1\r\na\r\n$1\r\nb\r\n", b":2\r\n"),
+            (b"*3\r\n$5\r\nLPUSH\r\n$4\r\njobs\r\n$1\r\nz\r\n", b":3\r\n"),
+            (b"*2\r\n$4\r\nLLEN\r\n$4\r\njobs\r\n", b":3\r\n"),
+            (
+                b"*4\r\n$6\r\nLRANGE\r\n$4\r\njobs\r\n$1\r\n0\r\n$2\r\n-1\r\n",
+                b"*3\r\n$1\r\nz\r\n$1\r\na\r\n$1\r\nb\r\n",
+            ),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        assert_eq!(2, conn.rpush("jobs", &[b"a", b"b"]).await.unwrap());
+        assert_eq!(3, conn.lpush("jobs", &[b"z"]).await.unwrap());
+        assert_eq!(3, conn.llen("jobs").await.unwrap());
+        assert_eq!(
+            vec![Bytes::from_static(b"z"), Bytes::from_static(b"a"), Bytes::from_static(b"b")],
+            conn.lrange("jobs", 0, -1).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    pub async fn test_pop() {
+        let addr = converse(vec![
+            (b"*2\r\n$4\r\nLPOP\r\n$4\r\njobs\r\n", b"$1\r\nz\r\n"),
+            (b"*2\r\n$4\r\nRPOP\r\n$4\r\njobs\r\n", b"$1\r\nb\r\n"),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        assert_eq!(Some(Bytes::from_static(b"z")), conn.lpop("jobs").await.unwrap());
+        assert_eq!(Some(Bytes::from_static(b"b")), conn.rpop("jobs").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_pop_empty() {
+        let addr = converse(vec![
+            (b"*2\r\n$4\r\nLPOP\r\n$4\r\nnone\r\n", b"$-1\r\n"),
+            (b"*2\r\n$4\r\nRPOP\r\n$4\r\nnone\r\n", b"_\r\n"),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        assert_eq!(None, conn.lpop("none").await.unwrap());
+        assert_eq!(None, conn.rpop("none").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_pop_count() {
+        let addr = converse(vec![
+            (b"*3\r\n$4\r\nLPOP\r\n$4\r\njobs\r\n$1\r\n2\r\n", b"*2\r\n$1\r\nz\r\n$1\r\na\r\n"),
+            (b"*3\r\n$4\r\nRPOP\r\n$4\r\nnone\r\n$1\r\n2\r\n", b"*-1\r\n"),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        assert_eq!(vec![Bytes::from_static(b"z"), Bytes::from_static(b"a")], conn.lpop_count("jobs", 2).await.unwrap());
+        assert!(conn.rpop_count("none", 2).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    pub async fn test_lrange_negative() {
+        let mut conn =
+            mock(b"*4\r\n$6\r\nLRANGE\r\n$4\r\njobs\r\n$2\r\n-2\r\n$2\r\n-1\r\n", b"*2\r\n$1\r\na\r\n$1\r\nb\r\n")
+                .await;
+
+        assert_eq!(
+            vec![Bytes::from_static(b"a"), Bytes::from_static(b"b")],
+            conn.lrange("jobs", -2, -1).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    pub async fn test_blpop() {
+        let mut conn = mock(
+            b"*4\r\n$5\r\nBLPOP\r\n$4\r\njobs\r\n$6\r\nurgent\r\n$3\r\n1.5\r\n",
+            b"*2\r\n$6\r\nurgent\r\n$1\r\na\r\n",
+        )
+        .await;
+
+        let popped = conn.blpop(&["jobs", "urgent"], Duration::from_millis(1500)).await.unwrap();
+
+        assert_eq!(Some((String::from("urgent"), Bytes::from_static(b"a"))), popped);
+    }
+
+    #[tokio::test]
+    pub async fn test_brpop_timed_out() {
+        let mut conn = mock(b"*3\r\n$5\r\nBRPOP\r\n$4\r\njobs\r\n$1\r\n1\r\n", b"*-1\r\n").await;
+
+        assert_eq!(None, conn.brpop(&["jobs"], Duration::from_secs(1)).await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_blpop_outlasts_read_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 34];
+            socket.read_exact(&mut buf).await.unwrap();
+            assert_eq!(b"*3\r\n$5\r\nBLPOP\r\n$4\r\njobs\r\n$3\r\n0.2\r\n", &buf);
+            // the server blocks for longer than the read timeout before timing out itself.
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            socket.write_all(b"*-1\r\n").await.unwrap();
+        });
+
+        let mut conn = Connection::connect(addr).await.unwrap();
+        conn.set_read_timeout(Some(Duration::from_millis(50)));
+
+        assert_eq!(None, conn.blpop(&["jobs"], Duration::from_millis(200)).await.unwrap());
+        assert_eq!(Some(Duration::from_millis(50)), conn.read_timeout());
+    }
+
+    #[tokio::test]
+    pub async fn test_blpop_cancelled() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut conn = Connection::connect(listener.local_addr().unwrap()).await.unwrap();
+        conn.set_read_timeout(Some(Duration::from_millis(50)));
+
+        // nothing ever answers, the caller gives up first.
+        let blpop = conn.blpop(&["jobs"], Duration::from_secs(5));
+        assert!(tokio::time::timeout(Duration::from_millis(20), blpop).await.is_err());
+
+        assert_eq!(Some(Duration::from_millis(50)), conn.read_timeout());
+    }
+
+    #[tokio::test]
+    pub async fn test_blpop_zero_timeout_cancelled() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut conn = Connection::connect(listener.local_addr().unwrap()).await.unwrap();
+        conn.set_read_timeout(Some(Duration::from_millis(50)));
+
+        let blpop = conn.blpop(&["jobs"], Duration::ZERO);
+        assert!(tokio::time::timeout(Duration::from_millis(100), blpop).await.is_err());
+
+        // the read timeout was only lifted for the cancelled call.
+        assert_eq!(Some(Duration::from_millis(50)), conn.read_timeout());
+    }
+
+    #[tokio::test]
+    pub async fn test_blpop_zero_timeout_waits_forever() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 32];
+            socket.read_exact(&mut buf).await.unwrap();
+            assert_eq!(b"*3\r\n$5\r\nBLPOP\r\n$4\r\njobs\r\n$1\r\n0\r\n", &buf);
+            // some other client pushes well after the read timeout would have fired.
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            socket.write_all(b"*2\r\n$4\r\njobs\r\n$1\r\na\r\n").await.unwrap();
+        });
+
+        let mut conn = Connection::connect(addr).await.unwrap();
+        conn.set_read_timeout(Some(Duration::from_millis(50)));
+
+        let popped = conn.blpop(&["jobs"], Duration::ZERO).await.unwrap();
+
+        assert_eq!(Some((String::from("jobs"), Bytes::from_static(b"a"))), popped);
+        assert_eq!(Some(Duration::from_millis(50)), conn.read_timeout());
+    }
+}
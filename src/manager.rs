@@ -0,0 +1,249 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::frame::{Error, Frame};
+use crate::{Connection, FromFrame, RedisError, RedisUrl, Result};
+
+/// How long `ConnectionManager` waits between reconnect attempts, the delay doubles after every failed attempt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Backoff {
+    pub base: Duration,
+    pub max: Duration,
+    /// Waits a random 50 to 100% of each delay, so clients that lost the same server don't all come back at once.
+    pub jitter: bool,
+    /// Gives up and returns the last connect error after this many attempts in a row. Only failures to reach the
+    /// server are retried, an error reply such as a rejected `AUTH` is returned right away.
+    pub attempts: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff { base: Duration::from_millis(100), max: Duration::from_secs(10), jitter: true, attempts: 8 }
+    }
+}
+
+impl Backoff {
+    pub fn new(base: Duration, max: Duration) -> Self {
+        Backoff { base, max, ..Backoff::default() }
+    }
+
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    /// The wait after the `attempt`th failure, counting from zero.
+    fn delay(&self, attempt: u32) -> Duration {
+        let delay = self.base.saturating_mul(2u32.saturating_pow(attempt)).min(self.max);
+        if !self.jitter {
+            return delay;
+        }
+        // `RandomState` is seeded randomly, good enough to spread clients apart without pulling in `rand`.
+        let random = RandomState::new().build_hasher().finish();
+        delay.mul_f64(0.5 + (random as f64 / u64::MAX as f64) / 2.0)
+    }
+}
+
+/// A connection that reconnects by itself. When the socket drops, the command in flight fails with
+/// `RedisError::Disconnected` and the next one reconnects first, with `AUTH` and `SELECT` replayed.
+pub struct ConnectionManager {
+    url: RedisUrl,
+    backoff: Backoff,
+    conn: Option<Connection>,
+}
+
+impl ConnectionManager {
+    /// Connects as described by a `redis://` or `rediss://` url, the first connect isn't retried.
+    pub async fn new(url: &str) -> Result<Self> {
+        ConnectionManager::with_backoff(url, Backoff::default()).await
+    }
+
+    pub async fn with_backoff(url: &str, backoff: Backoff) -> Result<Self> {
+        let url = RedisUrl::parse(url)?;
+        let conn = Connection::connect_to(&url).await?;
+        Ok(ConnectionManager { url, backoff, conn: Some(conn) })
+    }
+
+    /// Sends an arbitrary command and returns the reply as it is, error replies included.
+    pub async fn cmd<I, S>(&mut self, args: I) -> Result<Frame>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[u8]>,
+    {
+        let conn = self.connection().await?;
+        let reply = conn.cmd(args).await;
+        reply.map_err(|e| self.drop_broken(e))
+    }
+
+    /// Like `Connection::send_command`, an error reply from the server is returned as an `Err`.
+    pub async fn send_command(&mut self, args: &[&[u8]]) -> Result<Frame> {
+        match self.cmd(args).await? {
+            Frame::Error(e) => Err(Error::due_to_protocol(e).into()),
+            frame => Ok(frame),
+        }
+    }
+
+    pub async fn ping(&mut self) -> Result<()> {
+        match self.send_command(&[b"PING"]).await? {
+            Frame::Simple(s) if s == "PONG" => Ok(()),
+            frame => Err(Error::unexpected(&frame).into()),
+        }
+    }
+
+    pub async fn get<T>(&mut self, key: &str) -> Result<Option<T>>
+    where
+        T: FromFrame,
+    {
+        let frame = self.send_command(&[b"GET", key.as_bytes()]).await?;
+        Ok(Option::<T>::from_frame(frame)?)
+    }
+
+    pub async fn set(&mut self, key: &str, value: &[u8]) -> Result<()> {
+        match self.send_command(&[b"SET", key.as_bytes(), value]).await? {
+            Frame::Simple(s) if s == "OK" => Ok(()),
+            frame => Err(Error::unexpected(&frame).into()),
+        }
+    }
+
+    /// Switches to the logical database `db`, later reconnects select it too.
+    pub async fn select(&mut self, db: u32) -> Result<()> {
+        let conn = self.connection().await?;
+        let selected = conn.select(db).await;
+        selected.map_err(|e| self.drop_broken(e))?;
+        self.url.db = db;
+        Ok(())
+    }
+
+    async fn connection(&mut self) -> Result<&mut Connection> {
+        if self.conn.is_none() {
+            self.conn = Some(self.reconnect().await?);
+        }
+        Ok(self.conn.as_mut().unwrap())
+    }
+
+    /// Lets go of the connection if `e` broke it, so the next command reconnects. A timeout is still reported as
+    /// one, anything else that broke the connection as `Disconnected`.
+    fn drop_broken(&mut self, e: RedisError) -> RedisError {
+        if !self.conn.as_ref().is_some_and(Connection::is_broken) {
+            return e;
+        }
+        self.conn = None;
+        match e {
+            RedisError::Timeout => e,
+            _ => RedisError::Disconnected,
+        }
+    }
+
+    async fn reconnect(&self) -> Result<Connection> {
+        let mut attempt = 0;
+        loop {
+            match Connection::connect_to(&self.url).await {
+                Ok(conn) => return Ok(conn),
+                Err(e) if !is_transient(&e) || attempt + 1 >= self.backoff.attempts => return Err(e),
+                Err(_) => {
+                    tokio::time::sleep(self.backoff.delay(attempt)).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Whether the server couldn't be reached or dropped the connection, as opposed to turning it down with an error
+/// reply, e.g. to a wrong password, which waiting won't fix.
+fn is_transient(e: &RedisError) -> bool {
+    matches!(e, RedisError::Io(_) | RedisError::Timeout | RedisError::Disconnected | RedisError::ConnectionReset)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::test::converse_many;
+
+    const AUTH: (&[u8], &[u8]) = (b"*2\r\n$4\r\nAUTH\r\n$6\r\nsecret\r\n", b"+OK\r\n");
+    const SELECT: (&[u8], &[u8]) = (b"*2\r\n$6\r\nSELECT\r\n$1\r\n2\r\n", b"+OK\r\n");
+    const PING: (&[u8], &[u8]) = (b"*1\r\n$4\r\nPING\r\n", b"+PONG\r\n");
+
+    #[tokio::test]
+    pub async fn test_reconnect() {
+        let addr = converse_many(vec![vec![AUTH, SELECT, PING], vec![AUTH, SELECT, PING]]).await;
+        let mut conn = ConnectionManager::new(&format!("redis://:secret@{addr}/2")).await.unwrap();
+
+        conn.ping().await.unwrap();
+        // the server hangs up after the first conversation.
+        assert!(matches!(conn.ping().await, Err(RedisError::Disconnected)));
+        conn.ping().await.unwrap();
+    }
+
+    #[tokio::test]
+    pub async fn test_select() {
+        const SELECT_3: (&[u8], &[u8]) = (b"*2\r\n$6\r\nSELECT\r\n$1\r\n3\r\n", b"+OK\r\n");
+        let addr = converse_many(vec![vec![AUTH, SELECT, SELECT_3], vec![AUTH, SELECT_3, PING]]).await;
+        let mut conn = ConnectionManager::new(&format!("redis://:secret@{addr}/2")).await.unwrap();
+        assert_eq!(2, conn.conn.as_ref().unwrap().db());
+
+        conn.select(3).await.unwrap();
+        assert_eq!(3, conn.conn.as_ref().unwrap().db());
+
+        // the server hangs up, the reconnect selects the new database.
+        assert!(matches!(conn.ping().await, Err(RedisError::Disconnected)));
+        conn.ping().await.unwrap();
+        assert_eq!(3, conn.conn.as_ref().unwrap().db());
+    }
+
+    #[tokio::test]
+    pub async fn test_reconnect_gives_up() {
+        let addr = converse_many(vec![vec![PING]]).await;
+        let backoff = Backoff::new(Duration::from_millis(1), Duration::from_millis(5)).attempts(3);
+        let mut conn = ConnectionManager::with_backoff(&format!("redis://{addr}"), backoff).await.unwrap();
+
+        conn.ping().await.unwrap();
+        assert!(matches!(conn.ping().await, Err(RedisError::Disconnected)));
+        // nothing is listening anymore.
+        assert!(matches!(conn.ping().await, Err(RedisError::Io(_))));
+    }
+
+    #[tokio::test]
+    pub async fn test_reconnect_rejected() {
+        const WRONGPASS: (&[u8], &[u8]) = (
+            b"*2\r\n$4\r\nAUTH\r\n$6\r\nsecret\r\n",
+            b"-WRONGPASS invalid username-password pair or user is disabled.\r\n",
+        );
+        let addr = converse_many(vec![vec![AUTH, PING], vec![WRONGPASS]]).await;
+        let backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(10));
+        let mut conn = ConnectionManager::with_backoff(&format!("redis://:secret@{addr}"), backoff).await.unwrap();
+
+        conn.ping().await.unwrap();
+        assert!(matches!(conn.ping().await, Err(RedisError::Disconnected)));
+        // the password was changed in the meantime, no amount of waiting helps.
+        let reply = tokio::time::timeout(Duration::from_millis(500), conn.ping()).await.unwrap();
+        assert!(matches!(reply, Err(RedisError::Frame(Error::Protocol(_)))), "{reply:?}");
+    }
+
+    #[test]
+    pub fn test_backoff_delay() {
+        let backoff = Backoff::new(Duration::from_millis(100), Duration::from_secs(1)).jitter(false);
+
+        assert_eq!(Duration::from_millis(100), backoff.delay(0));
+        assert_eq!(Duration::from_millis(800), backoff.delay(3));
+        assert_eq!(Duration::from_secs(1), backoff.delay(4));
+        assert_eq!(Duration::from_secs(1), backoff.delay(40));
+    }
+
+    #[test]
+    pub fn test_backoff_jitter() {
+        let backoff = Backoff::new(Duration::from_millis(100), Duration::from_secs(1));
+
+        for attempt in 0..8 {
+            let delay = backoff.delay(attempt);
+            let max = Duration::from_millis(100 << attempt).min(Duration::from_secs(1));
+            assert!(max / 2 <= delay && delay <= max, "{delay:?}");
+        }
+    }
+}
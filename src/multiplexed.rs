@@ -0,0 +1,155 @@
+use std::collections::VecDeque;
+
+use tokio::sync::{mpsc, oneshot};
+
+use crate::frame::{Error, Frame};
+use crate::{command, Connection, FromFrame, RedisError, Result};
+
+type Request = (Frame, oneshot::Sender<Result<Frame>>);
+
+/// A connection that can be cloned and shared between tasks, a background task owns the socket and writes
+/// requests as they come in. Redis answers a connection's requests in order, so replies are handed back first in
+/// first out.
+///
+/// Out-of-band frames like RESP3 pushes would be mistaken for replies, don't subscribe on it.
+#[derive(Clone, Debug)]
+pub struct MultiplexedConnection {
+    requests: mpsc::UnboundedSender<Request>,
+}
+
+impl MultiplexedConnection {
+    /// Moves `conn` into a task spawned on the current runtime, it ends once every handle is dropped.
+    pub fn new(conn: Connection) -> Self {
+        let (requests, rx) = mpsc::unbounded_channel();
+        tokio::spawn(drive(conn, rx));
+        MultiplexedConnection { requests }
+    }
+
+    /// Sends an arbitrary command and returns the reply as it is, error replies included.
+    pub async fn cmd<I, S>(&self, args: I) -> Result<Frame>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[u8]>,
+    {
+        let (reply, rx) = oneshot::channel();
+        self.requests.send((command(args), reply)).map_err(|_| RedisError::ConnectionReset)?;
+        rx.await.map_err(|_| RedisError::ConnectionReset)?
+    }
+
+    /// Like `Connection::send_command`, an error reply from the server is returned as an `Err`.
+    pub async fn send_command(&self, args: &[&[u8]]) -> Result<Frame> {
+        match self.cmd(args).await? {
+            Frame::Error(e) => Err(Error::due_to_protocol(e).into()),
+            frame => Ok(frame),
+        }
+    }
+
+    pub async fn get<T>(&self, key: &str) -> Result<Option<T>>
+    where
+        T: FromFrame,
+    {
+        let frame = self.send_command(&[b"GET", key.as_bytes()]).await?;
+        Ok(Option::<T>::from_frame(frame)?)
+    }
+
+    pub async fn set(&self, key: &str, value: &[u8]) -> Result<()> {
+        match self.send_command(&[b"SET", key.as_bytes(), value]).await? {
+            Frame::Simple(s) if s == "OK" => Ok(()),
+            frame => Err(Error::unexpected(&frame).into()),
+        }
+    }
+}
+
+async fn drive(mut conn: Connection, mut requests: mpsc::UnboundedReceiver<Request>) {
+    let mut pending: VecDeque<oneshot::Sender<Result<Frame>>> = VecDeque::new();
+    let mut open = true;
+
+    loop {
+        tokio::select! {
+            request = requests.recv(), if open => match request {
+                Some((frame, reply)) => match conn.write_frame(&frame).await {
+                    Ok(()) => pending.push_back(reply),
+                    Err(e) => {
+                        let _ = reply.send(Err(e));
+                        break;
+                    }
+                },
+                // every handle is gone, answer what's still in flight and stop.
+                None => open = false,
+            },
+            frame = conn.read_frame(), if !pending.is_empty() => match frame {
+                Ok(Some(frame)) => {
+                    if let Some(reply) = pending.pop_front() {
+                        let _ = reply.send(Ok(frame));
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    if let Some(reply) = pending.pop_front() {
+                        let _ = reply.send(Err(e));
+                    }
+                    break;
+                }
+            },
+            else => return,
+        }
+    }
+
+    // the connection is broken, nothing still waiting will get its reply.
+    for reply in pending {
+        let _ = reply.send(Err(RedisError::ConnectionReset));
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use bytes::Bytes;
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::test::mock;
+
+    #[tokio::test]
+    pub async fn test_concurrent_gets() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // echoes every key back as its value, so each reply tells which request it answers.
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut server = Connection::from_stream(socket);
+            while let Some(Frame::Array(args)) = server.read_frame().await.unwrap() {
+                assert_eq!(Some("GET"), args[0].as_str());
+                server.write_frame(&args[1]).await.unwrap();
+            }
+        });
+
+        let conn = MultiplexedConnection::new(Connection::connect(addr).await.unwrap());
+        let tasks: Vec<_> = (0..100)
+            .map(|i| {
+                let conn = conn.clone();
+                tokio::spawn(async move { (i, conn.get::<String>(&format!("key:{i}")).await.unwrap()) })
+            })
+            .collect();
+
+        for task in tasks {
+            let (i, value) = task.await.unwrap();
+            assert_eq!(Some(format!("key:{i}")), value);
+        }
+    }
+
+    #[tokio::test]
+    pub async fn test_error_reply() {
+        let conn = MultiplexedConnection::new(mock(b"*1\r\n$4\r\nNOPE\r\n", b"-ERR unknown command 'NOPE'\r\n").await);
+
+        assert_eq!(Frame::Error(String::from("ERR unknown command 'NOPE'")), conn.cmd(["NOPE"]).await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_connection_closed() {
+        let conn = MultiplexedConnection::new(mock(b"*2\r\n$3\r\nGET\r\n$1\r\na\r\n", b"").await);
+
+        assert!(matches!(conn.get::<Bytes>("a").await, Err(RedisError::ConnectionReset)));
+        assert!(matches!(conn.get::<Bytes>("a").await, Err(RedisError::ConnectionReset)));
+    }
+}
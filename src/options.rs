@@ -0,0 +1,253 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use crate::RedisError;
+
+/// The condition `SET` checks before writing the key.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Existence {
+    /// `NX`, only set the key if it does not already exist.
+    NotExists,
+    /// `XX`, only set the key if it already exists.
+    Exists,
+}
+
+/// What `Connection::set_opts` did.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SetReply {
+    /// The key was written.
+    Written,
+    /// The `NX`/`XX` condition didn't hold, the key was left alone.
+    Skipped,
+    /// With `GET`, the value the key held before, `None` when there wasn't one. The server replies with it whether
+    /// or not the write happened.
+    Previous(Option<Bytes>),
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SetOptions {
+    /// `EX`, expire the key after this long, it has to be whole seconds.
+    pub ex: Option<Duration>,
+    /// `PX`, expire the key after this long, it has to be whole milliseconds. Only one of `ex` and `px` can be set.
+    pub px: Option<Duration>,
+    pub if_exists: Option<Existence>,
+    /// `KEEPTTL`, keep the key's current time to live instead of clearing it.
+    pub keep_ttl: bool,
+    /// `GET`, reply with the value the key held before the write.
+    pub get: bool,
+}
+
+impl SetOptions {
+    pub fn new() -> Self {
+        SetOptions::default()
+    }
+
+    /// Expires the key after `expire`, sent as seconds. Anything finer is rejected rather than rounded, use `px`.
+    pub fn ex(mut self, expire: Duration) -> Self {
+        self.ex = Some(expire);
+        self
+    }
+
+    /// Expires the key after `expire`, sent as milliseconds. Anything finer is rejected rather than rounded.
+    pub fn px(mut self, expire: Duration) -> Self {
+        self.px = Some(expire);
+        self
+    }
+
+    pub fn nx(mut self) -> Self {
+        self.if_exists = Some(Existence::NotExists);
+        self
+    }
+
+    pub fn xx(mut self) -> Self {
+        self.if_exists = Some(Existence::Exists);
+        self
+    }
+
+    pub fn keepttl(mut self) -> Self {
+        self.keep_ttl = true;
+        self
+    }
+
+    pub fn get(mut self) -> Self {
+        self.get = true;
+        self
+    }
+
+    pub(crate) fn to_args(&self) -> Result<Vec<String>, RedisError> {
+        let expire = match (self.ex, self.px) {
+            (Some(_), Some(_)) => return Err(RedisError::InvalidArgument(String::from("EX and PX can't be combined"))),
+            (Some(expire), None) if expire.subsec_nanos() != 0 => {
+                return Err(RedisError::InvalidArgument(String::from("EX takes whole seconds, use PX")));
+            }
+            (None, Some(expire)) if expire.subsec_nanos() % 1_000_000 != 0 => {
+                return Err(RedisError::InvalidArgument(String::from("PX takes whole milliseconds")));
+            }
+            (Some(expire), None) => Some(("EX", expire.as_secs())),
+            (None, Some(expire)) => Some(("PX", expire.as_millis() as u64)),
+            (None, None) => None,
+        };
+        if expire.is_some() && self.keep_ttl {
+            return Err(RedisError::InvalidArgument(String::from("an expiry and KEEPTTL can't be combined")));
+        }
+
+        let mut args = Vec::new();
+        if let Some((unit, value)) = expire {
+            // the server rejects a zero expiry with "invalid expire time".
+            if value == 0 {
+                return Err(RedisError::InvalidArgument(format!("{unit} has to be greater than zero")));
+            }
+            args.push(String::from(unit));
+            args.push(value.to_string());
+        }
+        match self.if_exists {
+            Some(Existence::NotExists) => args.push(String::from("NX")),
+            Some(Existence::Exists) => args.push(String::from("XX")),
+            None => {}
+        }
+        if self.keep_ttl {
+            args.push(String::from("KEEPTTL"));
+        }
+        if self.get {
+            args.push(String::from("GET"));
+        }
+        Ok(args)
+    }
+}
+
+/// `MATCH`, `COUNT` and `TYPE` for `SCAN` and the commands walking a single collection, all optional.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScanOptions {
+    /// A glob-style pattern, the server filters each page after reading it so a page can come back empty.
+    pub pattern: Option<String>,
+    /// Hints how many entries the server looks at per page.
+    pub count: Option<usize>,
+    /// `TYPE`, only keys holding this type such as `hash` or `zset`. Only `SCAN` accepts it.
+    pub key_type: Option<String>,
+}
+
+impl ScanOptions {
+    pub fn new() -> Self {
+        ScanOptions::default()
+    }
+
+    pub fn pattern(mut self, pattern: &str) -> Self {
+        self.pattern = Some(pattern.to_owned());
+        self
+    }
+
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    pub fn key_type(mut self, key_type: &str) -> Self {
+        self.key_type = Some(key_type.to_owned());
+        self
+    }
+
+    pub(crate) fn to_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(pattern) = &self.pattern {
+            args.push(String::from("MATCH"));
+            args.push(pattern.clone());
+        }
+        if let Some(count) = self.count {
+            args.push(String::from("COUNT"));
+            args.push(count.to_string());
+        }
+        if let Some(key_type) = &self.key_type {
+            args.push(String::from("TYPE"));
+            args.push(key_type.clone());
+        }
+        args
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_set_options_default() {
+        assert!(SetOptions::default().to_args().unwrap().is_empty());
+    }
+
+    #[test]
+    pub fn test_set_options_expire() {
+        let opts = SetOptions { ex: Some(Duration::from_secs(10)), ..Default::default() };
+        assert_eq!(vec!["EX", "10"], opts.to_args().unwrap());
+
+        let opts = SetOptions { px: Some(Duration::from_millis(1500)), ..Default::default() };
+        assert_eq!(vec!["PX", "1500"], opts.to_args().unwrap());
+
+        // a whole number of seconds asked for in milliseconds stays in milliseconds.
+        let opts = SetOptions::new().px(Duration::from_secs(2));
+        assert_eq!(vec!["PX", "2000"], opts.to_args().unwrap());
+    }
+
+    #[test]
+    pub fn test_set_options_existence() {
+        let opts = SetOptions { if_exists: Some(Existence::NotExists), ..Default::default() };
+        assert_eq!(vec!["NX"], opts.to_args().unwrap());
+
+        let opts = SetOptions {
+            px: Some(Duration::from_millis(250)),
+            if_exists: Some(Existence::Exists),
+            ..Default::default()
+        };
+        assert_eq!(vec!["PX", "250", "XX"], opts.to_args().unwrap());
+    }
+
+    #[test]
+    pub fn test_set_options_builder() {
+        let opts = SetOptions::new().ex(Duration::from_secs(10)).nx();
+        assert_eq!(vec!["EX", "10", "NX"], opts.to_args().unwrap());
+
+        let opts = SetOptions::new().px(Duration::from_millis(1500)).xx().get();
+        assert_eq!(vec!["PX", "1500", "XX", "GET"], opts.to_args().unwrap());
+
+        let opts = SetOptions::new().keepttl().get();
+        assert_eq!(vec!["KEEPTTL", "GET"], opts.to_args().unwrap());
+    }
+
+    #[test]
+    pub fn test_set_options_conflict() {
+        let opts = SetOptions::new().ex(Duration::from_secs(10)).keepttl();
+        assert!(matches!(opts.to_args(), Err(RedisError::InvalidArgument(_))));
+
+        let opts = SetOptions::new().ex(Duration::from_secs(10)).px(Duration::from_millis(500));
+        assert!(matches!(opts.to_args(), Err(RedisError::InvalidArgument(_))));
+        let opts = SetOptions::new().px(Duration::from_millis(500)).ex(Duration::from_secs(10));
+        assert!(matches!(opts.to_args(), Err(RedisError::InvalidArgument(_))));
+    }
+
+    #[test]
+    pub fn test_set_options_fractional_expiry() {
+        let opts = SetOptions::new().ex(Duration::from_millis(10_500));
+        assert!(matches!(opts.to_args(), Err(RedisError::InvalidArgument(_))));
+
+        let opts = SetOptions::new().px(Duration::from_micros(1500));
+        assert!(matches!(opts.to_args(), Err(RedisError::InvalidArgument(_))));
+    }
+
+    #[test]
+    pub fn test_set_options_zero_expiry() {
+        let opts = SetOptions::new().ex(Duration::ZERO);
+        assert!(matches!(opts.to_args(), Err(RedisError::InvalidArgument(_))));
+
+        let opts = SetOptions::new().px(Duration::ZERO);
+        assert!(matches!(opts.to_args(), Err(RedisError::InvalidArgument(_))));
+
+        assert_eq!(vec!["PX", "500"], SetOptions::new().px(Duration::from_millis(500)).to_args().unwrap());
+    }
+
+    #[test]
+    pub fn test_scan_options() {
+        assert!(ScanOptions::new().to_args().is_empty());
+
+        let opts = ScanOptions::new().pattern("user:*").count(100).key_type("hash");
+        assert_eq!(vec!["MATCH", "user:*", "COUNT", "100", "TYPE", "hash"], opts.to_args());
+    }
+}
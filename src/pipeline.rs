@@ -0,0 +1,128 @@
+use crate::frame::Frame;
+use crate::{command, Connection, Result};
+
+/// Queues several commands so they reach the server in a single write. Start one with `Pipeline::new`, queue commands
+/// with `push`, then send them all on a connection with `execute`.
+#[derive(Debug, Default)]
+pub struct Pipeline {
+    commands: Vec<Frame>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Pipeline::default()
+    }
+
+    pub fn push<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[u8]>,
+    {
+        self.commands.push(command(args));
+        self
+    }
+
+    /// Returns one reply per queued command in order, error replies are kept in place instead of aborting.
+    pub async fn execute(self, conn: &mut Connection) -> Result<Vec<Frame>> {
+        conn.write_frames(&self.commands).await?;
+
+        let mut replies = Vec::with_capacity(self.commands.len());
+        for _ in 0..self.commands.len() {
+            replies.push(conn.read_reply().await?);
+        }
+        Ok(replies)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use bytes::Bytes;
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::stream::Stream;
+    use crate::test::mock;
+
+    #[tokio::test]
+    pub async fn test_execute() {
+        let mut conn = mock(
+            b"*3\r\n$3\r\nSET\r\n$1\r\na\r\n$1\r\n1\r\n\
+              *3\r\n$3\r\nSET\r\n$1\r\nb\r\n$1\r\n2\r\n\
+              *3\r\n$3\r\nSET\r\n$1\r\nc\r\n$1\r\n3\r\n\
+              *2\r\n$3\r\nGET\r\n$1\r\na\r\n",
+            b"+OK\r\n+OK\r\n-ERR out of memory\r\n$1\r\n1\r\n",
+        )
+        .await;
+
+        let mut pipeline = Pipeline::new();
+        pipeline.push(["SET", "a", "1"]).push(["SET", "b", "2"]).push(["SET", "c", "3"]).push(["GET", "a"]);
+        let replies = pipeline.execute(&mut conn).await.unwrap();
+
+        assert_eq!(
+            vec![
+                Frame::Simple(String::from("OK")),
+                Frame::Simple(String::from("OK")),
+                Frame::Error(String::from("ERR out of memory")),
+                Frame::Bulk(Bytes::from_static(b"1")),
+            ],
+            replies
+        );
+    }
+
+    #[tokio::test]
+    pub async fn test_execute_pings() {
+        let mut conn =
+            mock(b"*1\r\n$4\r\nPING\r\n*1\r\n$4\r\nPING\r\n*1\r\n$4\r\nPING\r\n", b"+PONG\r\n+PONG\r\n+PONG\r\n").await;
+
+        let mut pipeline = Pipeline::new();
+        pipeline.push(["PING"]).push(["PING"]).push(["PING"]);
+
+        assert_eq!(vec![Frame::Simple(String::from("PONG")); 3], pipeline.execute(&mut conn).await.unwrap());
+    }
+
+    /// Answers every `SET` with `OK`, checking its value is the one `value` gives for its key.
+    async fn answer(mut server: Connection) {
+        while let Some(Frame::Array(args)) = server.read_frame().await.unwrap() {
+            let key: usize = args[1].as_str().unwrap().parse().unwrap();
+            assert_eq!(Some(&value(key)[..]), args[2].as_bytes());
+            server.write_frame(&Frame::Simple(String::from("OK"))).await.unwrap();
+        }
+    }
+
+    /// Every tenth value is large enough to be written as a slice of its own.
+    fn value(key: usize) -> Vec<u8> {
+        vec![b'a' + (key % 26) as u8; if key.is_multiple_of(10) { 4096 } else { 16 }]
+    }
+
+    fn sets(count: usize) -> Pipeline {
+        let mut pipeline = Pipeline::new();
+        for key in 0..count {
+            pipeline.push([b"SET".to_vec(), key.to_string().into_bytes(), value(key)]);
+        }
+        pipeline
+    }
+
+    #[tokio::test]
+    pub async fn test_execute_vectored() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { answer(Connection::from_stream(listener.accept().await.unwrap().0)).await });
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let replies = sets(10_000).execute(&mut conn).await.unwrap();
+
+        assert_eq!(vec![Frame::Simple(String::from("OK")); 10_000], replies);
+    }
+
+    #[tokio::test]
+    pub async fn test_execute_buffered() {
+        // an in-memory pipe has no vectored writes, everything goes through one buffer.
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(answer(Connection::new(Stream::Duplex(server))));
+        let mut conn = Connection::new(Stream::Duplex(client));
+
+        let replies = sets(100).execute(&mut conn).await.unwrap();
+
+        assert_eq!(vec![Frame::Simple(String::from("OK")); 100], replies);
+    }
+}
@@ -0,0 +1,335 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::{Connection, RedisError, Result};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// How many connections can be checked out at once, `get` waits once they all are.
+    pub max_size: usize,
+    /// Connections left unused for longer than this are closed instead of handed out again.
+    pub idle_timeout: Option<Duration>,
+    /// The read timeout every connection gets, put back when it's returned in case the borrower changed it.
+    pub read_timeout: Option<Duration>,
+}
+
+impl PoolConfig {
+    pub fn new(max_size: usize) -> Self {
+        PoolConfig { max_size, idle_timeout: None, read_timeout: None }
+    }
+
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+}
+
+/// A bounded set of connections to one server, cheap to clone and share between tasks. Connections are opened
+/// lazily, the first time nothing idle is left to hand out.
+#[derive(Clone)]
+pub struct Pool {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    addr: String,
+    config: PoolConfig,
+    permits: Arc<Semaphore>,
+    idle: Mutex<Vec<(Connection, Instant)>>,
+}
+
+impl Pool {
+    /// Fails with `InvalidArgument` when `size` is zero, `get` would wait forever.
+    pub fn new(addr: impl Into<String>, size: usize) -> Result<Self> {
+        Pool::with_config(addr, PoolConfig::new(size))
+    }
+
+    pub fn with_config(addr: impl Into<String>, config: PoolConfig) -> Result<Self> {
+        if config.max_size == 0 {
+            return Err(RedisError::InvalidArgument(String::from("a pool needs a max size of at least one")));
+        }
+        let permits = Arc::new(Semaphore::new(config.max_size));
+        let shared = Shared { addr: addr.into(), config, permits, idle: Mutex::new(Vec::new()) };
+        Ok(Pool { shared: Arc::new(shared) })
+    }
+
+    /// Checks a connection out, waiting while `max_size` of them are in use. It goes back to the pool when the
+    /// `PooledConnection` is dropped, unless it can't be handed on as it is: a read or write on it failed, a
+    /// command was cancelled before its reply was read, another database was selected or keys are still watched.
+    pub async fn get(&self) -> Result<PooledConnection> {
+        let permit = self.shared.permits.clone().acquire_owned().await.map_err(|_| RedisError::ConnectionReset)?;
+
+        let conn = match self.take_idle() {
+            Some(conn) => conn,
+            None => {
+                let mut conn = Connection::connect(self.shared.addr.as_str()).await?;
+                conn.set_read_timeout(self.shared.config.read_timeout);
+                conn
+            }
+        };
+        Ok(PooledConnection { conn: Some(conn), shared: self.shared.clone(), _permit: permit })
+    }
+
+    /// How many connections sit in the pool waiting to be checked out.
+    pub fn idle(&self) -> usize {
+        self.shared.idle.lock().unwrap().len()
+    }
+
+    fn take_idle(&self) -> Option<Connection> {
+        let mut idle = self.shared.idle.lock().unwrap();
+        if let Some(timeout) = self.shared.config.idle_timeout {
+            idle.retain(|(_, since)| since.elapsed() < timeout);
+        }
+        // the most recently returned one is the least likely to have been closed by the server.
+        idle.pop().map(|(conn, _)| conn)
+    }
+}
+
+impl std::fmt::Debug for Pool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pool")
+            .field("addr", &self.shared.addr)
+            .field("config", &self.shared.config)
+            .field("idle", &self.idle())
+            .finish()
+    }
+}
+
+/// A connection checked out of a `Pool`, derefs to `Connection`.
+pub struct PooledConnection {
+    conn: Option<Connection>,
+    shared: Arc<Shared>,
+    // declared last so the slot is only freed after the connection is back in the pool.
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Deref for PooledConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.conn.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(mut conn) = self.conn.take() {
+            if !conn.is_broken() && !conn.has_pending() && conn.db() == 0 && !conn.is_watching() {
+                conn.set_read_timeout(self.shared.config.read_timeout);
+                self.shared.idle.lock().unwrap().push((conn, Instant::now()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::Frame;
+
+    /// Answers `PING` with `PONG`, `BLPOP` never and anything else with `OK` on every connection it accepts, except
+    /// the ones listed in `hang_up` which are closed right away. Returns the address and a count of the connections
+    /// accepted so far.
+    async fn server(hang_up: &'static [usize]) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let accepted = Arc::new(AtomicUsize::new(0));
+
+        let counter = accepted.clone();
+        tokio::spawn(async move {
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                if hang_up.contains(&counter.fetch_add(1, Ordering::SeqCst)) {
+                    continue;
+                }
+                tokio::spawn(async move {
+                    let mut conn = Connection::from_stream(socket);
+                    while let Ok(Some(Frame::Array(args))) = conn.read_frame().await {
+                        let reply = match args[0].as_str() {
+                            Some("PING") => "PONG",
+                            Some("BLPOP") => continue,
+                            _ => "OK",
+                        };
+                        conn.write_frame(&Frame::Simple(String::from(reply))).await.unwrap();
+                    }
+                });
+            }
+        });
+
+        (addr, accepted)
+    }
+
+    #[tokio::test]
+    pub async fn test_reuse() {
+        let (addr, accepted) = server(&[]).await;
+        let pool = Pool::new(addr, 2).unwrap();
+
+        pool.get().await.unwrap().ping().await.unwrap();
+        assert_eq!(1, pool.idle());
+        pool.get().await.unwrap().ping().await.unwrap();
+
+        assert_eq!(1, accepted.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    pub async fn test_concurrent_checkouts() {
+        let (addr, accepted) = server(&[]).await;
+        let pool = Pool::new(addr, 4).unwrap();
+        pool.get().await.unwrap().ping().await.unwrap();
+
+        // more tasks than both the single idle connection and the max size.
+        let tasks: Vec<_> = (0..16)
+            .map(|_| {
+                let pool = pool.clone();
+                tokio::spawn(async move {
+                    let mut conn = pool.get().await.unwrap();
+                    conn.ping().await.unwrap();
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(4, accepted.load(Ordering::SeqCst));
+        assert_eq!(4, pool.idle());
+    }
+
+    #[tokio::test]
+    pub async fn test_waits_when_exhausted() {
+        let (addr, _) = server(&[]).await;
+        let pool = Pool::new(addr, 1).unwrap();
+
+        let conn = pool.get().await.unwrap();
+        assert!(tokio::time::timeout(Duration::from_millis(50), pool.get()).await.is_err());
+
+        drop(conn);
+        let mut conn = tokio::time::timeout(Duration::from_millis(50), pool.get()).await.unwrap().unwrap();
+        conn.ping().await.unwrap();
+    }
+
+    #[tokio::test]
+    pub async fn test_broken_discarded() {
+        let (addr, accepted) = server(&[0]).await;
+        let pool = Pool::new(addr, 1).unwrap();
+
+        let mut conn = pool.get().await.unwrap();
+        assert!(conn.ping().await.is_err());
+        drop(conn);
+        assert_eq!(0, pool.idle());
+
+        pool.get().await.unwrap().ping().await.unwrap();
+        assert_eq!(2, accepted.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    pub async fn test_cancelled_command_discarded() {
+        let (addr, accepted) = server(&[]).await;
+        let pool = Pool::new(addr, 1).unwrap();
+
+        // the server never answers `BLPOP`, the reply would be left for the next checkout to read.
+        let mut conn = pool.get().await.unwrap();
+        let blpop = conn.blpop(&["jobs"], Duration::ZERO);
+        assert!(tokio::time::timeout(Duration::from_millis(20), blpop).await.is_err());
+        drop(conn);
+        assert_eq!(0, pool.idle());
+
+        pool.get().await.unwrap().ping().await.unwrap();
+        assert_eq!(2, accepted.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    pub async fn test_selected_db_discarded() {
+        let (addr, _) = server(&[]).await;
+        let pool = Pool::new(addr, 1).unwrap();
+
+        pool.get().await.unwrap().select(3).await.unwrap();
+        assert_eq!(0, pool.idle());
+
+        let mut conn = pool.get().await.unwrap();
+        conn.select(3).await.unwrap();
+        conn.select(0).await.unwrap();
+        drop(conn);
+        assert_eq!(1, pool.idle());
+    }
+
+    #[tokio::test]
+    pub async fn test_watching_discarded() {
+        let (addr, _) = server(&[]).await;
+        let pool = Pool::new(addr, 1).unwrap();
+
+        pool.get().await.unwrap().watch(&["balance"]).await.unwrap();
+        assert_eq!(0, pool.idle());
+
+        let mut conn = pool.get().await.unwrap();
+        conn.watch(&["balance"]).await.unwrap();
+        conn.unwatch().await.unwrap();
+        drop(conn);
+        assert_eq!(1, pool.idle());
+    }
+
+    #[tokio::test]
+    pub async fn test_unfinished_transaction_discarded() {
+        let (addr, accepted) = server(&[]).await;
+        let pool = Pool::new(addr, 1).unwrap();
+
+        let mut conn = pool.get().await.unwrap();
+        drop(conn.multi().await.unwrap());
+        drop(conn);
+        assert_eq!(0, pool.idle());
+
+        pool.get().await.unwrap().ping().await.unwrap();
+        assert_eq!(2, accepted.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    pub async fn test_idle_timeout() {
+        let (addr, accepted) = server(&[]).await;
+        let pool = Pool::with_config(addr, PoolConfig::new(1).idle_timeout(Duration::from_millis(20))).unwrap();
+
+        pool.get().await.unwrap().ping().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        pool.get().await.unwrap().ping().await.unwrap();
+
+        assert_eq!(2, accepted.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    pub async fn test_read_timeout_restored() {
+        let (addr, accepted) = server(&[]).await;
+        let pool = Pool::with_config(addr, PoolConfig::new(1).read_timeout(Duration::from_millis(50))).unwrap();
+
+        let mut conn = pool.get().await.unwrap();
+        assert_eq!(Some(Duration::from_millis(50)), conn.read_timeout());
+        conn.set_read_timeout(None);
+        conn.ping().await.unwrap();
+        drop(conn);
+
+        assert_eq!(Some(Duration::from_millis(50)), pool.get().await.unwrap().read_timeout());
+        assert_eq!(1, accepted.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    pub fn test_zero_size_rejected() {
+        assert!(matches!(Pool::new("127.0.0.1:6379", 0), Err(RedisError::InvalidArgument(_))));
+    }
+}
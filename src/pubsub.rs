@@ -0,0 +1,374 @@
+use std::collections::{HashSet, VecDeque};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use bytes::Bytes;
+use futures_core::Stream;
+
+use crate::frame::{Error, Frame};
+use crate::{command, Connection, FromFrame, RedisError, Result};
+
+/// A message published to one of the channels a `Subscriber` listens on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    pub channel: String,
+    /// The pattern `channel` matched, for a message that arrived through `psubscribe`.
+    pub pattern: Option<String>,
+    pub payload: Bytes,
+}
+
+/// A connection in subscriber mode, Redis only accepts (un)subscribe commands on it from then on. Channel and
+/// pattern subscriptions can be mixed, a message matching both arrives once for each.
+///
+/// Messages can be awaited one at a time with `next_message`, or consumed as a `Stream` which ends when the server
+/// closes the connection. The stream ignores the connection's read timeout.
+pub struct Subscriber {
+    conn: Connection,
+    /// Messages that arrived while waiting for a subscription to be confirmed.
+    pending: VecDeque<Message>,
+    channels: HashSet<String>,
+    patterns: HashSet<String>,
+}
+
+/// What the server sends to a subscribed connection, as arrays under RESP2 and pushes under RESP3.
+enum Event {
+    Message(Message),
+    /// `subscribe`, `unsubscribe` or their pattern counterparts, `name` is `None` when an unsubscribe from
+    /// everything found nothing to leave.
+    Confirmed {
+        kind: String,
+        name: Option<String>,
+    },
+}
+
+impl Connection {
+    /// Subscribes to `channels` and returns once the server confirmed every one of them.
+    pub async fn subscribe(self, channels: &[&str]) -> Result<Subscriber> {
+        let mut subscriber = Subscriber::new(self);
+        subscriber.subscribe_more(channels).await?;
+        Ok(subscriber)
+    }
+
+    /// Subscribes to every channel matching one of the glob-style `patterns`, e.g. `cache:*`.
+    pub async fn psubscribe(self, patterns: &[&str]) -> Result<Subscriber> {
+        let mut subscriber = Subscriber::new(self);
+        subscriber.psubscribe(patterns).await?;
+        Ok(subscriber)
+    }
+
+    /// Returns how many subscribers the message was delivered to. A subscribed connection can't publish, this needs
+    /// a connection of its own.
+    pub async fn publish(&mut self, channel: &str, message: &[u8]) -> Result<u64> {
+        self.query(&[b"PUBLISH", channel.as_bytes(), message]).await
+    }
+}
+
+impl Subscriber {
+    fn new(conn: Connection) -> Self {
+        Subscriber { conn, pending: VecDeque::new(), channels: HashSet::new(), patterns: HashSet::new() }
+    }
+
+    /// Waits for the next message, `None` once the server closed the connection.
+    pub async fn next_message(&mut self) -> Result<Option<Message>> {
+        if let Some(message) = self.pending.pop_front() {
+            return Ok(Some(message));
+        }
+
+        loop {
+            let frame = match self.conn.read_frame().await? {
+                Some(frame) => frame,
+                None => return Ok(None),
+            };
+            match Event::parse(frame)? {
+                Event::Message(message) => return Ok(Some(message)),
+                Event::Confirmed { kind, name } => self.track(&kind, name),
+            }
+        }
+    }
+
+    pub async fn subscribe_more(&mut self, channels: &[&str]) -> Result<()> {
+        self.request("subscribe", channels).await
+    }
+
+    /// Leaves `channels`, or every channel when it's empty. Pattern subscriptions are kept either way.
+    pub async fn unsubscribe(&mut self, channels: &[&str]) -> Result<()> {
+        self.request("unsubscribe", channels).await
+    }
+
+    pub async fn psubscribe(&mut self, patterns: &[&str]) -> Result<()> {
+        self.request("psubscribe", patterns).await
+    }
+
+    /// Leaves `patterns`, or every pattern when it's empty. Channel subscriptions are kept either way.
+    pub async fn punsubscribe(&mut self, patterns: &[&str]) -> Result<()> {
+        self.request("punsubscribe", patterns).await
+    }
+
+    /// Sends `kind` and waits until the server confirmed each name, or for an empty list until none of that kind is
+    /// left. Messages read in the meantime are kept for `next_message`.
+    async fn request(&mut self, kind: &str, names: &[&str]) -> Result<()> {
+        let command_name = kind.to_ascii_uppercase();
+        let mut args = vec![command_name.as_str()];
+        args.extend(names);
+        self.conn.write_frame(&command(args)).await?;
+
+        let mut remaining = names.len();
+        loop {
+            let frame = self.conn.read_frame().await?.ok_or(RedisError::ConnectionReset)?;
+            match Event::parse(frame)? {
+                Event::Message(message) => self.pending.push_back(message),
+                Event::Confirmed { kind: confirmed, name } => {
+                    self.track(&confirmed, name);
+                    if confirmed != kind {
+                        continue;
+                    }
+                    remaining = remaining.saturating_sub(1);
+                    let left = if kind.starts_with('p') { &self.patterns } else { &self.channels };
+                    if remaining == 0 && (!names.is_empty() || left.is_empty()) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    fn track(&mut self, kind: &str, name: Option<String>) {
+        let Some(name) = name else { return };
+        match kind {
+            "subscribe" => self.channels.insert(name),
+            "unsubscribe" => self.channels.remove(&name),
+            "psubscribe" => self.patterns.insert(name),
+            _ => self.patterns.remove(&name),
+        };
+    }
+}
+
+impl Stream for Subscriber {
+    type Item = Result<Message>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let Some(message) = this.pending.pop_front() {
+            return Poll::Ready(Some(Ok(message)));
+        }
+
+        loop {
+            let frame = match ready!(this.conn.poll_read_frame(cx)) {
+                Ok(Some(frame)) => frame,
+                Ok(None) => return Poll::Ready(None),
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            };
+            match Event::parse(frame) {
+                Ok(Event::Message(message)) => return Poll::Ready(Some(Ok(message))),
+                Ok(Event::Confirmed { kind, name }) => this.track(&kind, name),
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            }
+        }
+    }
+}
+
+impl Event {
+    fn parse(frame: Frame) -> Result<Event> {
+        let items = match frame {
+            Frame::Array(items) | Frame::Push(items) => items,
+            Frame::Error(e) => return Err(Error::due_to_protocol(e).into()),
+            frame => return Err(Error::unexpected(&frame).into()),
+        };
+        let unexpected = |items: Vec<Frame>| Error::unexpected(&Frame::Array(items));
+
+        let kind = items.first().and_then(Frame::as_str).map(str::to_owned);
+        match (kind, items.len()) {
+            (Some(kind), 3) if kind == "message" => {
+                let [_, channel, payload]: [Frame; 3] = items.try_into().map_err(unexpected)?;
+                let channel = String::from_frame(channel)?;
+                Ok(Event::Message(Message { channel, pattern: None, payload: Bytes::from_frame(payload)? }))
+            }
+            (Some(kind), 4) if kind == "pmessage" => {
+                let [_, pattern, channel, payload]: [Frame; 4] = items.try_into().map_err(unexpected)?;
+                let (channel, pattern) = (String::from_frame(channel)?, Some(String::from_frame(pattern)?));
+                Ok(Event::Message(Message { channel, pattern, payload: Bytes::from_frame(payload)? }))
+            }
+            (Some(kind), 3) if ["subscribe", "unsubscribe", "psubscribe", "punsubscribe"].contains(&kind.as_str()) => {
+                let [_, name, _]: [Frame; 3] = items.try_into().map_err(unexpected)?;
+                Ok(Event::Confirmed { kind, name: Option::<String>::from_frame(name)? })
+            }
+            _ => Err(unexpected(items).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::test::{converse, mock, next};
+
+    fn message(channel: &str, payload: &'static [u8]) -> Message {
+        Message { channel: String::from(channel), pattern: None, payload: Bytes::from_static(payload) }
+    }
+
+    #[tokio::test]
+    pub async fn test_subscribe() {
+        let addr = converse(vec![(
+            b"*3\r\n$9\r\nSUBSCRIBE\r\n$4\r\nnews\r\n$6\r\nsports\r\n",
+            b"*3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:1\r\n\
+              *3\r\n$7\r\nmessage\r\n$4\r\nnews\r\n$5\r\nhello\r\n\
+              *3\r\n$9\r\nsubscribe\r\n$6\r\nsports\r\n:2\r\n\
+              *3\r\n$7\r\nmessage\r\n$6\r\nsports\r\n$4\r\ngoal\r\n",
+        )])
+        .await;
+        let conn = Connection::connect(addr).await.unwrap();
+
+        let mut subscriber = conn.subscribe(&["news", "sports"]).await.unwrap();
+
+        // the first message came in before `sports` was confirmed.
+        assert_eq!(Some(message("news", b"hello")), subscriber.next_message().await.unwrap());
+        assert_eq!(Some(message("sports", b"goal")), subscriber.next_message().await.unwrap());
+        assert_eq!(None, subscriber.next_message().await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_subscribe_resp3_push() {
+        let addr = converse(vec![(
+            b"*2\r\n$9\r\nSUBSCRIBE\r\n$4\r\nnews\r\n",
+            b">3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:1\r\n>3\r\n$7\r\nmessage\r\n$4\r\nnews\r\n$5\r\nhello\r\n",
+        )])
+        .await;
+        let conn = Connection::connect(addr).await.unwrap();
+
+        let mut subscriber = conn.subscribe(&["news"]).await.unwrap();
+
+        assert_eq!(Some(message("news", b"hello")), subscriber.next_message().await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_subscribe_more_and_unsubscribe() {
+        let addr = converse(vec![
+            (b"*2\r\n$9\r\nSUBSCRIBE\r\n$4\r\nnews\r\n", b"*3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:1\r\n"),
+            (b"*2\r\n$9\r\nSUBSCRIBE\r\n$6\r\nsports\r\n", b"*3\r\n$9\r\nsubscribe\r\n$6\r\nsports\r\n:2\r\n"),
+            (
+                b"*1\r\n$11\r\nUNSUBSCRIBE\r\n",
+                b"*3\r\n$11\r\nunsubscribe\r\n$4\r\nnews\r\n:1\r\n*3\r\n$11\r\nunsubscribe\r\n$6\r\nsports\r\n:0\r\n",
+            ),
+        ])
+        .await;
+        let conn = Connection::connect(addr).await.unwrap();
+
+        let mut subscriber = conn.subscribe(&["news"]).await.unwrap();
+        subscriber.subscribe_more(&["sports"]).await.unwrap();
+        subscriber.unsubscribe(&[]).await.unwrap();
+    }
+
+    #[tokio::test]
+    pub async fn test_subscribe_error() {
+        let addr = converse(vec![(b"*1\r\n$9\r\nSUBSCRIBE\r\n", b"-ERR wrong number of arguments\r\n")]).await;
+        let conn = Connection::connect(addr).await.unwrap();
+
+        assert!(conn.subscribe(&[]).await.is_err());
+    }
+
+    #[tokio::test]
+    pub async fn test_psubscribe() {
+        let addr = converse(vec![(
+            b"*2\r\n$10\r\nPSUBSCRIBE\r\n$7\r\ncache:*\r\n",
+            b"*3\r\n$10\r\npsubscribe\r\n$7\r\ncache:*\r\n:1\r\n\
+              *4\r\n$8\r\npmessage\r\n$7\r\ncache:*\r\n$10\r\ncache:user\r\n$2\r\n42\r\n",
+        )])
+        .await;
+        let conn = Connection::connect(addr).await.unwrap();
+
+        let mut subscriber = conn.psubscribe(&["cache:*"]).await.unwrap();
+
+        let expected = Message {
+            channel: String::from("cache:user"),
+            pattern: Some(String::from("cache:*")),
+            payload: Bytes::from_static(b"42"),
+        };
+        assert_eq!(Some(expected), subscriber.next_message().await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_message_shapes() {
+        let frame = Frame::Array(vec![
+            Frame::Bulk(Bytes::from_static(b"pmessage")),
+            Frame::Bulk(Bytes::from_static(b"news.*")),
+            Frame::Bulk(Bytes::from_static(b"news.tech")),
+            Frame::Bulk(Bytes::from_static(b"hello")),
+        ]);
+        let Ok(Event::Message(message)) = Event::parse(frame) else { panic!("not a message") };
+        assert_eq!(
+            Message {
+                channel: String::from("news.tech"),
+                pattern: Some(String::from("news.*")),
+                payload: Bytes::from_static(b"hello"),
+            },
+            message
+        );
+
+        let frame = Frame::Array(vec![
+            Frame::Bulk(Bytes::from_static(b"message")),
+            Frame::Bulk(Bytes::from_static(b"news.tech")),
+            Frame::Bulk(Bytes::from_static(b"hello")),
+        ]);
+        let Ok(Event::Message(message)) = Event::parse(frame) else { panic!("not a message") };
+        assert_eq!(self::message("news.tech", b"hello"), message);
+
+        // a `pmessage` without its pattern is malformed.
+        let frame = Frame::Array(vec![
+            Frame::Bulk(Bytes::from_static(b"pmessage")),
+            Frame::Bulk(Bytes::from_static(b"news.tech")),
+            Frame::Bulk(Bytes::from_static(b"hello")),
+        ]);
+        assert!(Event::parse(frame).is_err());
+    }
+
+    #[tokio::test]
+    pub async fn test_mixed_subscriptions() {
+        let addr = converse(vec![
+            (b"*2\r\n$9\r\nSUBSCRIBE\r\n$4\r\nnews\r\n", b"*3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:1\r\n"),
+            (b"*2\r\n$10\r\nPSUBSCRIBE\r\n$2\r\nn*\r\n", b"*3\r\n$10\r\npsubscribe\r\n$2\r\nn*\r\n:2\r\n"),
+            (
+                b"*1\r\n$11\r\nUNSUBSCRIBE\r\n",
+                // a pattern is still subscribed, so the count doesn't drop to zero.
+                b"*3\r\n$11\r\nunsubscribe\r\n$4\r\nnews\r\n:1\r\n\
+                  *4\r\n$8\r\npmessage\r\n$2\r\nn*\r\n$4\r\nnews\r\n$5\r\nhello\r\n",
+            ),
+        ])
+        .await;
+        let conn = Connection::connect(addr).await.unwrap();
+
+        let mut subscriber = conn.subscribe(&["news"]).await.unwrap();
+        subscriber.psubscribe(&["n*"]).await.unwrap();
+        subscriber.unsubscribe(&[]).await.unwrap();
+
+        let message = subscriber.next_message().await.unwrap().unwrap();
+        assert_eq!(("news", Some("n*")), (message.channel.as_str(), message.pattern.as_deref()));
+    }
+
+    #[tokio::test]
+    pub async fn test_publish() {
+        let mut conn = mock(b"*3\r\n$7\r\nPUBLISH\r\n$4\r\nnews\r\n$5\r\nhello\r\n", b":2\r\n").await;
+
+        assert_eq!(2, conn.publish("news", b"hello").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_publish_error() {
+        let mut conn =
+            mock(b"*3\r\n$7\r\nPUBLISH\r\n$4\r\nnews\r\n$5\r\nhello\r\n", b"-NOPERM no permissions\r\n").await;
+
+        assert!(matches!(conn.publish("news", b"hello").await, Err(RedisError::Frame(Error::Protocol(_)))));
+    }
+
+    #[tokio::test]
+    pub async fn test_stream() {
+        let addr = converse(vec![(
+            b"*2\r\n$9\r\nSUBSCRIBE\r\n$4\r\nnews\r\n",
+            b">3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:1\r\n>3\r\n$7\r\nmessage\r\n$4\r\nnews\r\n$5\r\nhello\r\n",
+        )])
+        .await;
+        let mut subscriber = Connection::connect(addr).await.unwrap().subscribe(&["news"]).await.unwrap();
+
+        assert_eq!(Some(message("news", b"hello")), next(&mut subscriber).await.transpose().unwrap());
+        assert!(next(&mut subscriber).await.is_none());
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_core::Stream;
+
+use crate::frame::{Error, Frame};
+use crate::{Connection, FromFrame, Result, ScanOptions};
+
+/// Turns the entries array of a page into items.
+type Entries<T> = fn(Frame) -> std::result::Result<Vec<T>, Error>;
+
+/// Requests one page, the connection is handed back along with the reply so the next page can use it.
+type Fetch<'a> = Pin<Box<dyn Future<Output = (&'a mut Connection, Result<Frame>)> + Send + 'a>>;
+
+/// The entries a `SCAN`-like command walks through, keys for `SCAN` itself. A page is only requested once the
+/// previous one is used up.
+///
+/// Redis may return an entry more than once if the collection changes during the walk.
+pub struct ScanStream<'a, T = Bytes> {
+    /// What goes before the cursor, e.g. `SCAN` or `HSCAN key`.
+    command: Vec<Bytes>,
+    /// What goes after the cursor, `MATCH`, `COUNT` and `TYPE`.
+    options: Vec<Bytes>,
+    /// Turns the entries of a page into items.
+    entries: Entries<T>,
+    items: VecDeque<T>,
+    state: State<'a>,
+}
+
+enum State<'a> {
+    /// Waiting to request the page at this cursor.
+    Idle(&'a mut Connection, u64),
+    Fetching(Fetch<'a>),
+    Done,
+}
+
+impl Connection {
+    /// Walks the keyspace, narrowed down by `opts`.
+    pub fn scan(&mut self, opts: ScanOptions) -> ScanStream<'_> {
+        self.scanner(vec![Bytes::from_static(b"SCAN")], opts, Vec::from_frame)
+    }
+
+    /// Sets up a stream for `command` followed by the cursor and the options.
+    pub(crate) fn scanner<T>(
+        &mut self,
+        command: Vec<Bytes>,
+        opts: ScanOptions,
+        entries: Entries<T>,
+    ) -> ScanStream<'_, T> {
+        let options = opts.to_args().into_iter().map(Bytes::from).collect();
+        ScanStream { command, options, entries, items: VecDeque::new(), state: State::Idle(self, 0) }
+    }
+}
+
+impl<'a, T> ScanStream<'a, T> {
+    fn fetch(&self, conn: &'a mut Connection, cursor: u64) -> Fetch<'a> {
+        let mut args = self.command.clone();
+        args.push(Bytes::from(cursor.to_string()));
+        args.extend(self.options.iter().cloned());
+
+        Box::pin(async move {
+            let args: Vec<&[u8]> = args.iter().map(|arg| &arg[..]).collect();
+            let reply = conn.send_command(&args).await;
+            (conn, reply)
+        })
+    }
+}
+
+impl<T> Stream for ScanStream<'_, T>
+where
+    T: Unpin,
+{
+    type Item = Result<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(item) = this.items.pop_front() {
+                return Poll::Ready(Some(Ok(item)));
+            }
+
+            match std::mem::replace(&mut this.state, State::Done) {
+                State::Idle(conn, cursor) => this.state = State::Fetching(this.fetch(conn, cursor)),
+                State::Fetching(mut fetch) => {
+                    let (conn, reply) = match fetch.as_mut().poll(cx) {
+                        Poll::Ready(fetched) => fetched,
+                        Poll::Pending => {
+                            this.state = State::Fetching(fetch);
+                            return Poll::Pending;
+                        }
+                    };
+                    let (cursor, items) = match reply.and_then(|frame| page(frame, this.entries)) {
+                        Ok(page) => page,
+                        Err(e) => return Poll::Ready(Some(Err(e))),
+                    };
+                    this.items.extend(items);
+                    // a page can be empty while the walk goes on, only the cursor coming back to 0 ends it.
+                    if cursor != 0 {
+                        this.state = State::Idle(conn, cursor);
+                    }
+                }
+                State::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+/// Splits a reply into the next cursor and the items of this page.
+fn page<T>(frame: Frame, entries: Entries<T>) -> Result<(u64, Vec<T>)> {
+    match frame {
+        Frame::Array(items) if items.len() == 2 => {
+            let [cursor, page]: [Frame; 2] =
+                items.try_into().map_err(|items| Error::unexpected(&Frame::Array(items)))?;
+            Ok((u64::from_frame(cursor)?, entries(page)?))
+        }
+        frame => Err(Error::unexpected(&frame).into()),
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::test::{converse, next};
+
+    pub(crate) async fn collect<S, T>(mut stream: S) -> Vec<T>
+    where
+        S: Stream<Item = Result<T>> + Unpin,
+    {
+        let mut items = Vec::new();
+        while let Some(item) = next(&mut stream).await {
+            items.push(item.unwrap());
+        }
+        items
+    }
+
+    #[tokio::test]
+    pub async fn test_scan() {
+        let addr = converse(vec![
+            (b"*2\r\n$4\r\nSCAN\r\n$1\r\n0\r\n", b"*2\r\n$2\r\n17\r\n*2\r\n$1\r\na\r\n$1\r\nb\r\n"),
+            (b"*2\r\n$4\r\nSCAN\r\n$2\r\n17\r\n", b"*2\r\n$1\r\n0\r\n*1\r\n$1\r\nc\r\n"),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let keys = collect(conn.scan(ScanOptions::new())).await;
+
+        assert_eq!(vec![Bytes::from_static(b"a"), Bytes::from_static(b"b"), Bytes::from_static(b"c")], keys);
+    }
+
+    #[tokio::test]
+    pub async fn test_scan_empty_page() {
+        let addr = converse(vec![
+            (
+                b"*6\r\n$4\r\nSCAN\r\n$1\r\n0\r\n$5\r\nMATCH\r\n$6\r\nuser:*\r\n$5\r\nCOUNT\r\n$3\r\n100\r\n",
+                b"*2\r\n$1\r\n9\r\n*0\r\n",
+            ),
+            (
+                b"*6\r\n$4\r\nSCAN\r\n$1\r\n9\r\n$5\r\nMATCH\r\n$6\r\nuser:*\r\n$5\r\nCOUNT\r\n$3\r\n100\r\n",
+                b"*2\r\n$1\r\n0\r\n*1\r\n$6\r\nuser:1\r\n",
+            ),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let keys = collect(conn.scan(ScanOptions::new().pattern("user:*").count(100))).await;
+
+        assert_eq!(vec![Bytes::from_static(b"user:1")], keys);
+    }
+
+    #[tokio::test]
+    pub async fn test_scan_type() {
+        let addr = converse(vec![
+            (
+                b"*4\r\n$4\r\nSCAN\r\n$1\r\n0\r\n$4\r\nTYPE\r\n$4\r\nhash\r\n",
+                b"*2\r\n$1\r\n5\r\n*1\r\n$6\r\nuser:1\r\n",
+            ),
+            (
+                b"*4\r\n$4\r\nSCAN\r\n$1\r\n5\r\n$4\r\nTYPE\r\n$4\r\nhash\r\n",
+                b"*2\r\n$1\r\n0\r\n*1\r\n$6\r\nuser:2\r\n",
+            ),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let keys = collect(conn.scan(ScanOptions::new().key_type("hash"))).await;
+
+        assert_eq!(vec![Bytes::from_static(b"user:1"), Bytes::from_static(b"user:2")], keys);
+    }
+
+    #[tokio::test]
+    pub async fn test_scan_error() {
+        let addr = converse(vec![(b"*2\r\n$4\r\nSCAN\r\n$1\r\n0\r\n", b"-ERR invalid cursor\r\n")]).await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+        let mut stream = conn.scan(ScanOptions::default());
+
+        assert!(next(&mut stream).await.unwrap().is_err());
+        assert!(next(&mut stream).await.is_none());
+    }
+}
@@ -0,0 +1,125 @@
+use sha1_smol::Sha1;
+
+use crate::frame::{Error, Frame};
+use crate::{Connection, RedisError, Result};
+
+/// A Lua script run with `EVALSHA`, its SHA1 is computed once up front. The first call on a server that hasn't
+/// seen the script yet gets `NOSCRIPT` back and falls back to `EVAL`, which also makes the server cache it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Script {
+    code: String,
+    hash: String,
+}
+
+impl Script {
+    pub fn new(code: &str) -> Self {
+        Script { code: code.to_owned(), hash: Sha1::from(code).digest().to_string() }
+    }
+
+    /// The script's SHA1 as lowercase hex, the name `EVALSHA` knows it by.
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    pub async fn invoke(&self, conn: &mut Connection, keys: &[&str], args: &[&[u8]]) -> Result<Frame> {
+        match conn.evalsha(&self.hash, keys, args).await {
+            Err(RedisError::Frame(Error::Protocol(e))) if e.starts_with("NOSCRIPT") => {
+                conn.eval(&self.code, keys, args).await
+            }
+            reply => reply,
+        }
+    }
+}
+
+impl Connection {
+    /// Runs a Lua script with `EVAL`, `keys` end up in `KEYS` and `args` in `ARGV`. Returns whatever the script
+    /// returns, an error raised by it is an `Err`.
+    pub async fn eval(&mut self, script: &str, keys: &[&str], args: &[&[u8]]) -> Result<Frame> {
+        self.run_script(b"EVAL", script, keys, args).await
+    }
+
+    /// Runs a script the server already has cached, by its SHA1. Fails with `NOSCRIPT` when it isn't cached, which
+    /// `Script` takes care of.
+    pub async fn evalsha(&mut self, sha1: &str, keys: &[&str], args: &[&[u8]]) -> Result<Frame> {
+        self.run_script(b"EVALSHA", sha1, keys, args).await
+    }
+
+    /// Sends `<cmd> <script> <numkeys> <keys...> <args...>`.
+    async fn run_script(&mut self, cmd: &[u8], script: &str, keys: &[&str], args: &[&[u8]]) -> Result<Frame> {
+        let numkeys = keys.len().to_string();
+        let mut all = vec![cmd, script.as_bytes(), numkeys.as_bytes()];
+        all.extend(keys.iter().map(|key| key.as_bytes()));
+        all.extend_from_slice(args);
+        self.send_command(&all).await
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::test::{converse, mock};
+
+    #[test]
+    pub fn test_hash() {
+        // what `SCRIPT LOAD "return 1"` replies with.
+        assert_eq!("e0e1f9fabfc9d4800c877a703b823ac0578ff8db", Script::new("return 1").hash());
+    }
+
+    #[tokio::test]
+    pub async fn test_eval() {
+        let mut conn = mock(
+            b"*5\r\n$4\r\nEVAL\r\n$33\r\nreturn redis.call('GET', KEYS[1])\r\n$1\r\n1\r\n$3\r\nfoo\r\n$1\r\nx\r\n",
+            b"$3\r\nbar\r\n",
+        )
+        .await;
+
+        let reply = conn.eval("return redis.call('GET', KEYS[1])", &["foo"], &[b"x"]).await.unwrap();
+
+        assert_eq!(Frame::Bulk(Bytes::from_static(b"bar")), reply);
+    }
+
+    #[tokio::test]
+    pub async fn test_evalsha_noscript() {
+        let mut conn = mock(
+            b"*3\r\n$7\r\nEVALSHA\r\n$40\r\ne0e1f9fabfc9d4800c877a703b823ac0578ff8db\r\n$1\r\n0\r\n",
+            b"-NOSCRIPT No matching script. Please use EVAL.\r\n",
+        )
+        .await;
+
+        let err = conn.evalsha("e0e1f9fabfc9d4800c877a703b823ac0578ff8db", &[], &[]).await.unwrap_err();
+
+        assert!(err.to_string().contains("NOSCRIPT"), "{err}");
+    }
+
+    #[tokio::test]
+    pub async fn test_script_falls_back_to_eval() {
+        let addr = converse(vec![
+            (
+                b"*3\r\n$7\r\nEVALSHA\r\n$40\r\ne0e1f9fabfc9d4800c877a703b823ac0578ff8db\r\n$1\r\n0\r\n",
+                b"-NOSCRIPT No matching script. Please use EVAL.\r\n",
+            ),
+            (b"*3\r\n$4\r\nEVAL\r\n$8\r\nreturn 1\r\n$1\r\n0\r\n", b":1\r\n"),
+            // the server has it cached now.
+            (b"*3\r\n$7\r\nEVALSHA\r\n$40\r\ne0e1f9fabfc9d4800c877a703b823ac0578ff8db\r\n$1\r\n0\r\n", b":1\r\n"),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+        let script = Script::new("return 1");
+
+        assert_eq!(Frame::Integer(1), script.invoke(&mut conn, &[], &[]).await.unwrap());
+        assert_eq!(Frame::Integer(1), script.invoke(&mut conn, &[], &[]).await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_script_error() {
+        let mut conn = mock(
+            b"*3\r\n$7\r\nEVALSHA\r\n$40\r\ne0e1f9fabfc9d4800c877a703b823ac0578ff8db\r\n$1\r\n0\r\n",
+            b"-ERR user_script:1: oops\r\n",
+        )
+        .await;
+
+        assert!(Script::new("return 1").invoke(&mut conn, &[], &[]).await.is_err());
+    }
+}
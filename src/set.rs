@@ -0,0 +1,132 @@
+use std::collections::HashSet;
+
+use bytes::Bytes;
+
+use crate::{Connection, FromFrame, Result, ScanOptions, ScanStream};
+
+impl Connection {
+    /// Walks the members of a set with `SSCAN`.
+    pub fn sscan(&mut self, key: &str, opts: ScanOptions) -> ScanStream<'_> {
+        let command = vec![Bytes::from_static(b"SSCAN"), Bytes::copy_from_slice(key.as_bytes())];
+        self.scanner(command, opts, Vec::from_frame)
+    }
+
+    /// Returns how many of `members` were added, members already in the set don't count.
+    pub async fn sadd(&mut self, key: &str, members: &[&[u8]]) -> Result<u64> {
+        let mut args = vec![b"SADD" as &[u8], key.as_bytes()];
+        args.extend_from_slice(members);
+        self.query(&args).await
+    }
+
+    /// Returns how many of `members` were in the set and got removed.
+    pub async fn srem(&mut self, key: &str, members: &[&[u8]]) -> Result<u64> {
+        let mut args = vec![b"SREM" as &[u8], key.as_bytes()];
+        args.extend_from_slice(members);
+        self.query(&args).await
+    }
+
+    pub async fn sismember(&mut self, key: &str, member: &[u8]) -> Result<bool> {
+        self.query(&[b"SISMEMBER", key.as_bytes(), member]).await
+    }
+
+    pub async fn scard(&mut self, key: &str) -> Result<u64> {
+        self.query(&[b"SCARD", key.as_bytes()]).await
+    }
+
+    /// Every member of the set, in no particular order.
+    pub async fn smembers(&mut self, key: &str) -> Result<Vec<Bytes>> {
+        self.query(&[b"SMEMBERS", key.as_bytes()]).await
+    }
+
+    /// Like `smembers`, collected into a `HashSet` for membership checks.
+    pub async fn smembers_set(&mut self, key: &str) -> Result<HashSet<Bytes>> {
+        self.query(&[b"SMEMBERS", key.as_bytes()]).await
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::scan::test::collect;
+    use crate::test::{converse, mock};
+
+    #[tokio::test]
+    pub async fn test_sadd_duplicates() {
+        let mut conn = mock(b"*5\r\n$4\r\nSADD\r\n$4\r\ntags\r\n$1\r\na\r\n$1\r\nb\r\n$1\r\na\r\n", b":2\r\n").await;
+
+        assert_eq!(2, conn.sadd("tags", &[b"a", b"b", b"a"]).await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_srem() {
+        let mut conn = mock(b"*4\r\n$4\r\nSREM\r\n$4\r\ntags\r\n$1\r\na\r\n$1\r\nz\r\n", b":1\r\n").await;
+
+        assert_eq!(1, conn.srem("tags", &[b"a", b"z"]).await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_sismember() {
+        let mut conn = mock(b"*3\r\n$9\r\nSISMEMBER\r\n$4\r\ntags\r\n$1\r\na\r\n", b":1\r\n").await;
+        assert!(conn.sismember("tags", b"a").await.unwrap());
+
+        let mut conn = mock(b"*3\r\n$9\r\nSISMEMBER\r\n$4\r\ntags\r\n$1\r\nz\r\n", b":0\r\n").await;
+        assert!(!conn.sismember("tags", b"z").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_scard() {
+        let mut conn = mock(b"*2\r\n$5\r\nSCARD\r\n$4\r\ntags\r\n", b":3\r\n").await;
+
+        assert_eq!(3, conn.scard("tags").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_smembers() {
+        let mut conn = mock(b"*2\r\n$8\r\nSMEMBERS\r\n$4\r\ntags\r\n", b"*2\r\n$1\r\na\r\n$1\r\nb\r\n").await;
+        assert_eq!(vec![Bytes::from_static(b"a"), Bytes::from_static(b"b")], conn.smembers("tags").await.unwrap());
+
+        let mut conn = mock(b"*2\r\n$8\r\nSMEMBERS\r\n$4\r\ntags\r\n", b"~2\r\n$1\r\na\r\n$1\r\nb\r\n").await;
+        assert_eq!(vec![Bytes::from_static(b"a"), Bytes::from_static(b"b")], conn.smembers("tags").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_smembers_set() {
+        let mut conn = mock(b"*2\r\n$8\r\nSMEMBERS\r\n$4\r\ntags\r\n", b"~2\r\n$1\r\na\r\n$1\r\nb\r\n").await;
+
+        let members = conn.smembers_set("tags").await.unwrap();
+
+        assert_eq!(HashSet::from([Bytes::from_static(b"a"), Bytes::from_static(b"b")]), members);
+    }
+
+    #[tokio::test]
+    pub async fn test_sscan() {
+        let addr = converse(vec![(
+            b"*5\r\n$5\r\nSSCAN\r\n$4\r\ntags\r\n$1\r\n0\r\n$5\r\nMATCH\r\n$2\r\nr*\r\n",
+            b"*2\r\n$1\r\n0\r\n*2\r\n$4\r\nrust\r\n$5\r\nredis\r\n",
+        )])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let members = collect(conn.sscan("tags", ScanOptions::new().pattern("r*"))).await;
+
+        assert_eq!(vec![Bytes::from_static(b"rust"), Bytes::from_static(b"redis")], members);
+    }
+
+    #[tokio::test]
+    pub async fn test_sscan_pages() {
+        let addr = converse(vec![
+            (b"*3\r\n$5\r\nSSCAN\r\n$4\r\ntags\r\n$1\r\n0\r\n", b"*2\r\n$1\r\n6\r\n*1\r\n$4\r\nrust\r\n"),
+            (
+                b"*3\r\n$5\r\nSSCAN\r\n$4\r\ntags\r\n$1\r\n6\r\n",
+                b"*2\r\n$1\r\n0\r\n*2\r\n$5\r\nredis\r\n$5\r\ntokio\r\n",
+            ),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let members = collect(conn.sscan("tags", ScanOptions::new())).await;
+
+        let expected = [&b"rust"[..], b"redis", b"tokio"].map(Bytes::from_static);
+        assert_eq!(expected.to_vec(), members);
+    }
+}
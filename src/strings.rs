@@ -0,0 +1,72 @@
+use crate::{Connection, Result};
+
+impl Connection {
+    /// Increments the integer stored at `key` by one, a missing key counts as `0`.
+    pub async fn incr(&mut self, key: &str) -> Result<i64> {
+        self.query(&[b"INCR", key.as_bytes()]).await
+    }
+
+    pub async fn decr(&mut self, key: &str) -> Result<i64> {
+        self.query(&[b"DECR", key.as_bytes()]).await
+    }
+
+    pub async fn incr_by(&mut self, key: &str, delta: i64) -> Result<i64> {
+        self.query(&[b"INCRBY", key.as_bytes(), delta.to_string().as_bytes()]).await
+    }
+
+    pub async fn incr_by_float(&mut self, key: &str, delta: f64) -> Result<f64> {
+        self.query(&[b"INCRBYFLOAT", key.as_bytes(), delta.to_string().as_bytes()]).await
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::frame::Error;
+    use crate::test::mock;
+    use crate::RedisError;
+
+    #[tokio::test]
+    pub async fn test_incr() {
+        let mut conn = mock(b"*2\r\n$4\r\nINCR\r\n$7\r\ncounter\r\n", b":11\r\n").await;
+
+        assert_eq!(11, conn.incr("counter").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_decr() {
+        let mut conn = mock(b"*2\r\n$4\r\nDECR\r\n$7\r\ncounter\r\n", b":-1\r\n").await;
+
+        assert_eq!(-1, conn.decr("counter").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_incr_by() {
+        let mut conn = mock(b"*3\r\n$6\r\nINCRBY\r\n$7\r\ncounter\r\n$3\r\n-15\r\n", b":-5\r\n").await;
+
+        assert_eq!(-5, conn.incr_by("counter", -15).await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_incr_not_an_integer() {
+        let mut conn =
+            mock(b"*2\r\n$4\r\nINCR\r\n$3\r\nfoo\r\n", b"-ERR value is not an integer or out of range\r\n").await;
+
+        let err = conn.incr("foo").await.unwrap_err();
+
+        assert!(matches!(err, RedisError::Frame(Error::Protocol(e)) if e.starts_with("ERR value is not an integer")));
+    }
+
+    #[tokio::test]
+    pub async fn test_incr_by_float() {
+        let mut conn = mock(b"*3\r\n$11\r\nINCRBYFLOAT\r\n$5\r\nprice\r\n$3\r\n0.5\r\n", b"$4\r\n10.5\r\n").await;
+
+        assert_eq!(10.5, conn.incr_by_float("price", 0.5).await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_incr_by_float_invalid_reply() {
+        let mut conn = mock(b"*3\r\n$11\r\nINCRBYFLOAT\r\n$5\r\nprice\r\n$3\r\n0.5\r\n", b"$3\r\nabc\r\n").await;
+
+        assert!(matches!(conn.incr_by_float("price", 0.5).await, Err(RedisError::Frame(Error::Protocol(_)))));
+    }
+}
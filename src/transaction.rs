@@ -0,0 +1,344 @@
+use crate::frame::{Error, Frame};
+use crate::{command, Connection, RedisError, Result};
+
+/// A `MULTI` block, it borrows the connection so nothing else can be sent until it's executed or discarded. Each
+/// queued command is a round trip, use a `Transaction` to send the whole block at once.
+///
+/// Dropping it without calling either, e.g. after `queue` failed, leaves the server waiting for `EXEC`. The
+/// connection is marked broken then, so a `Pool` closes it instead of handing it out again.
+pub struct Multi<'a> {
+    conn: &'a mut Connection,
+    /// Set once `EXEC` or `DISCARD` was answered, the server is out of `MULTI` then.
+    done: bool,
+}
+
+impl Connection {
+    pub async fn multi(&mut self) -> Result<Multi<'_>> {
+        match self.send_command(&[b"MULTI"]).await? {
+            Frame::Simple(s) if s == "OK" => Ok(Multi { conn: self, done: false }),
+            frame => Err(Error::unexpected(&frame).into()),
+        }
+    }
+}
+
+impl Connection {
+    /// Makes the next `EXEC` abort if any of `keys` is modified before it, the watch ends with that `EXEC`.
+    ///
+    /// Watches belong to the connection, so the `MULTI`/`EXEC` it guards has to be sent on this same connection.
+    /// With a `Pool`, keep the `PooledConnection` checked out from `watch` until `exec`.
+    pub async fn watch(&mut self, keys: &[&str]) -> Result<()> {
+        let mut args = vec![b"WATCH" as &[u8]];
+        args.extend(keys.iter().map(|key| key.as_bytes()));
+        match self.send_command(&args).await? {
+            Frame::Simple(s) if s == "OK" => {
+                self.watching = true;
+                Ok(())
+            }
+            frame => Err(Error::unexpected(&frame).into()),
+        }
+    }
+
+    pub async fn unwatch(&mut self) -> Result<()> {
+        match self.send_command(&[b"UNWATCH"]).await? {
+            Frame::Simple(s) if s == "OK" => {
+                self.watching = false;
+                Ok(())
+            }
+            frame => Err(Error::unexpected(&frame).into()),
+        }
+    }
+}
+
+impl Multi<'_> {
+    /// Queues a command, the server only checks its syntax now and runs it on `exec`.
+    pub async fn queue<I, S>(&mut self, args: I) -> Result<&mut Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[u8]>,
+    {
+        match self.conn.cmd(args).await? {
+            Frame::Simple(s) if s == "QUEUED" => Ok(self),
+            Frame::Error(e) => Err(Error::due_to_protocol(e).into()),
+            frame => Err(Error::unexpected(&frame).into()),
+        }
+    }
+
+    /// Runs the queued commands and returns their replies in order, error replies are kept in place. Fails with
+    /// `TransactionAborted` when a watched key was modified in the meantime.
+    pub async fn exec(mut self) -> Result<Vec<Frame>> {
+        let reply = self.conn.send_command(&[b"EXEC"]).await;
+        self.done = true;
+        self.conn.watching = false;
+        match reply? {
+            Frame::Array(replies) => Ok(replies),
+            Frame::Nil => Err(RedisError::TransactionAborted),
+            frame => Err(Error::unexpected(&frame).into()),
+        }
+    }
+
+    pub async fn discard(mut self) -> Result<()> {
+        let reply = self.conn.send_command(&[b"DISCARD"]).await;
+        self.done = true;
+        self.conn.watching = false;
+        match reply? {
+            Frame::Simple(s) if s == "OK" => Ok(()),
+            frame => Err(Error::unexpected(&frame).into()),
+        }
+    }
+}
+
+impl Drop for Multi<'_> {
+    fn drop(&mut self) {
+        // `DISCARD` can't be sent from here, whatever is sent next on this connection would get queued instead.
+        if !self.done {
+            self.conn.broken = true;
+        }
+    }
+}
+
+/// Commands buffered to run as one `MULTI`/`EXEC` block. Nothing is sent until `execute`, which writes `MULTI`, the
+/// commands and `EXEC` at once like a `Pipeline` does.
+#[derive(Debug, Default)]
+pub struct Transaction {
+    commands: Vec<Frame>,
+}
+
+impl Transaction {
+    pub fn new() -> Self {
+        Transaction::default()
+    }
+
+    pub fn queue<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[u8]>,
+    {
+        self.commands.push(command(args));
+        self
+    }
+
+    /// Runs the queued commands and returns their replies in order, error replies are kept in place. A command the
+    /// server refused to queue, e.g. for a wrong number of arguments, fails the whole block with an error naming
+    /// it, and `TransactionAborted` means a watched key was modified in the meantime.
+    pub async fn execute(self, conn: &mut Connection) -> Result<Vec<Frame>> {
+        let count = self.commands.len();
+        let mut frames = Vec::with_capacity(count + 2);
+        frames.push(command(["MULTI"]));
+        frames.extend(self.commands);
+        frames.push(command(["EXEC"]));
+        conn.write_frames(&frames).await?;
+
+        // every reply is read before looking at them, so none is left behind for the next command.
+        let multi = conn.read_reply().await?;
+        let mut queued = Vec::with_capacity(count);
+        for _ in 0..count {
+            queued.push(conn.read_reply().await?);
+        }
+        let exec = conn.read_reply().await?;
+        conn.watching = false;
+
+        match multi {
+            Frame::Simple(s) if s == "OK" => {}
+            Frame::Error(e) => return Err(Error::due_to_protocol(e).into()),
+            frame => return Err(Error::unexpected(&frame).into()),
+        }
+        for (i, reply) in queued.into_iter().enumerate() {
+            match reply {
+                Frame::Simple(s) if s == "QUEUED" => {}
+                Frame::Error(e) => {
+                    let reason = format!("command {} of {count} was rejected, {e}", i + 1);
+                    return Err(Error::due_to_protocol(reason).into());
+                }
+                frame => return Err(Error::unexpected(&frame).into()),
+            }
+        }
+        match exec {
+            Frame::Array(replies) => Ok(replies),
+            Frame::Nil => Err(RedisError::TransactionAborted),
+            Frame::Error(e) => Err(Error::due_to_protocol(e).into()),
+            frame => Err(Error::unexpected(&frame).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::test::{converse, mock};
+
+    #[tokio::test]
+    pub async fn test_exec() {
+        let addr = converse(vec![
+            (b"*1\r\n$5\r\nMULTI\r\n", b"+OK\r\n"),
+            (b"*3\r\n$3\r\nSET\r\n$1\r\na\r\n$1\r\n1\r\n", b"+QUEUED\r\n"),
+            (b"*3\r\n$3\r\nSET\r\n$1\r\nb\r\n$1\r\n2\r\n", b"+QUEUED\r\n"),
+            (b"*2\r\n$4\r\nINCR\r\n$1\r\nb\r\n", b"+QUEUED\r\n"),
+            (b"*1\r\n$4\r\nEXEC\r\n", b"*3\r\n+OK\r\n+OK\r\n:3\r\n"),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let mut tx = conn.multi().await.unwrap();
+        tx.queue(["SET", "a", "1"]).await.unwrap().queue(["SET", "b", "2"]).await.unwrap();
+        tx.queue(["INCR", "b"]).await.unwrap();
+        let replies = tx.exec().await.unwrap();
+
+        assert_eq!(
+            vec![Frame::Simple(String::from("OK")), Frame::Simple(String::from("OK")), Frame::Integer(3)],
+            replies
+        );
+    }
+
+    #[tokio::test]
+    pub async fn test_discard() {
+        let addr = converse(vec![
+            (b"*1\r\n$5\r\nMULTI\r\n", b"+OK\r\n"),
+            (b"*2\r\n$3\r\nGET\r\n$1\r\na\r\n", b"+QUEUED\r\n"),
+            (b"*1\r\n$7\r\nDISCARD\r\n", b"+OK\r\n"),
+            (b"*2\r\n$3\r\nGET\r\n$1\r\na\r\n", b"$1\r\n1\r\n"),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let mut tx = conn.multi().await.unwrap();
+        tx.queue(["GET", "a"]).await.unwrap();
+        tx.discard().await.unwrap();
+
+        assert_eq!(Some(Bytes::from_static(b"1")), conn.get("a").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_queue_rejected() {
+        let addr = converse(vec![
+            (b"*1\r\n$5\r\nMULTI\r\n", b"+OK\r\n"),
+            (b"*1\r\n$3\r\nSET\r\n", b"-ERR wrong number of arguments for 'set' command\r\n"),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let mut tx = conn.multi().await.unwrap();
+
+        assert!(matches!(tx.queue(["SET"]).await, Err(RedisError::Frame(Error::Protocol(_)))));
+        drop(tx);
+        assert!(conn.is_broken());
+    }
+
+    #[tokio::test]
+    pub async fn test_exec_after_rejected() {
+        let addr = converse(vec![
+            (b"*1\r\n$5\r\nMULTI\r\n", b"+OK\r\n"),
+            (b"*1\r\n$3\r\nSET\r\n", b"-ERR wrong number of arguments for 'set' command\r\n"),
+            (b"*2\r\n$4\r\nINCR\r\n$1\r\nb\r\n", b"+QUEUED\r\n"),
+            (b"*1\r\n$4\r\nEXEC\r\n", b"-EXECABORT Transaction discarded because of previous errors.\r\n"),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let mut tx = conn.multi().await.unwrap();
+        assert!(tx.queue(["SET"]).await.is_err());
+        tx.queue(["INCR", "b"]).await.unwrap();
+
+        let err = tx.exec().await.unwrap_err();
+        assert!(err.to_string().contains("EXECABORT"), "{err}");
+        assert!(!conn.is_broken());
+    }
+
+    #[tokio::test]
+    pub async fn test_exec_aborted() {
+        let addr = converse(vec![
+            (b"*1\r\n$5\r\nMULTI\r\n", b"+OK\r\n"),
+            (b"*2\r\n$4\r\nINCR\r\n$1\r\nb\r\n", b"+QUEUED\r\n"),
+            (b"*1\r\n$4\r\nEXEC\r\n", b"*-1\r\n"),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let mut tx = conn.multi().await.unwrap();
+        tx.queue(["INCR", "b"]).await.unwrap();
+
+        assert!(matches!(tx.exec().await, Err(RedisError::TransactionAborted)));
+    }
+
+    #[tokio::test]
+    pub async fn test_watch_aborted() {
+        let addr = converse(vec![
+            (b"*2\r\n$5\r\nWATCH\r\n$7\r\nbalance\r\n", b"+OK\r\n"),
+            (b"*2\r\n$3\r\nGET\r\n$7\r\nbalance\r\n", b"$2\r\n10\r\n"),
+            (b"*1\r\n$5\r\nMULTI\r\n", b"+OK\r\n"),
+            (b"*3\r\n$3\r\nSET\r\n$7\r\nbalance\r\n$1\r\n5\r\n", b"+QUEUED\r\n"),
+            (b"*1\r\n$4\r\nEXEC\r\n", b"_\r\n"),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        conn.watch(&["balance"]).await.unwrap();
+        let balance: i64 = conn.get("balance").await.unwrap().unwrap();
+        let mut tx = conn.multi().await.unwrap();
+        tx.queue([String::from("SET"), String::from("balance"), (balance - 5).to_string()]).await.unwrap();
+
+        assert!(matches!(tx.exec().await, Err(RedisError::TransactionAborted)));
+    }
+
+    #[tokio::test]
+    pub async fn test_execute() {
+        let mut conn = mock(
+            b"*1\r\n$5\r\nMULTI\r\n\
+              *3\r\n$3\r\nSET\r\n$1\r\na\r\n$1\r\n1\r\n\
+              *2\r\n$4\r\nINCR\r\n$1\r\nb\r\n\
+              *1\r\n$4\r\nEXEC\r\n",
+            b"+OK\r\n+QUEUED\r\n+QUEUED\r\n*2\r\n+OK\r\n:3\r\n",
+        )
+        .await;
+
+        let mut tx = Transaction::new();
+        tx.queue(["SET", "a", "1"]).queue(["INCR", "b"]);
+        let replies = tx.execute(&mut conn).await.unwrap();
+
+        assert_eq!(vec![Frame::Simple(String::from("OK")), Frame::Integer(3)], replies);
+    }
+
+    #[tokio::test]
+    pub async fn test_execute_rejected() {
+        let addr = converse(vec![
+            (
+                b"*1\r\n$5\r\nMULTI\r\n*2\r\n$4\r\nINCR\r\n$1\r\nb\r\n*1\r\n$3\r\nSET\r\n*1\r\n$4\r\nEXEC\r\n",
+                b"+OK\r\n+QUEUED\r\n-ERR wrong number of arguments for 'set' command\r\n\
+                  -EXECABORT Transaction discarded because of previous errors.\r\n",
+            ),
+            (b"*1\r\n$4\r\nPING\r\n", b"+PONG\r\n"),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let mut tx = Transaction::new();
+        tx.queue(["INCR", "b"]).queue(["SET"]);
+        let err = tx.execute(&mut conn).await.unwrap_err();
+
+        assert!(err.to_string().contains("command 2 of 2 was rejected"), "{err}");
+        // the `EXECABORT` was read too, the connection is still in step.
+        conn.ping().await.unwrap();
+    }
+
+    #[tokio::test]
+    pub async fn test_execute_aborted() {
+        let mut conn = mock(
+            b"*1\r\n$5\r\nMULTI\r\n*2\r\n$4\r\nINCR\r\n$1\r\nb\r\n*1\r\n$4\r\nEXEC\r\n",
+            b"+OK\r\n+QUEUED\r\n*-1\r\n",
+        )
+        .await;
+
+        let mut tx = Transaction::new();
+        tx.queue(["INCR", "b"]);
+
+        assert!(matches!(tx.execute(&mut conn).await, Err(RedisError::TransactionAborted)));
+    }
+
+    #[tokio::test]
+    pub async fn test_unwatch() {
+        let mut conn = mock(b"*1\r\n$7\r\nUNWATCH\r\n", b"+OK\r\n").await;
+
+        conn.unwatch().await.unwrap();
+    }
+}
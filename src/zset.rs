@@ -0,0 +1,154 @@
+use bytes::Bytes;
+
+use crate::{Connection, FromFrame, Result, ScanOptions, ScanStream};
+
+impl Connection {
+    /// Adds or updates `members` with their scores, returns how many members are new.
+    pub async fn zadd(&mut self, key: &str, members: &[(f64, &str)]) -> Result<u64> {
+        let scores: Vec<String> = members.iter().map(|(score, _)| score.to_string()).collect();
+        let mut args = vec![b"ZADD" as &[u8], key.as_bytes()];
+        for (score, (_, member)) in scores.iter().zip(members) {
+            args.push(score.as_bytes());
+            args.push(member.as_bytes());
+        }
+        self.query(&args).await
+    }
+
+    /// `None` when the member or the key doesn't exist.
+    pub async fn zscore(&mut self, key: &str, member: &str) -> Result<Option<f64>> {
+        self.query(&[b"ZSCORE", key.as_bytes(), member.as_bytes()]).await
+    }
+
+    /// The member's position ordered by score from low to high, `None` when it isn't in the set.
+    pub async fn zrank(&mut self, key: &str, member: &str) -> Result<Option<u64>> {
+        self.query(&[b"ZRANK", key.as_bytes(), member.as_bytes()]).await
+    }
+
+    /// The members ranked from `start` to `stop` inclusive, negative ranks count from the highest score.
+    pub async fn zrange(&mut self, key: &str, start: i64, stop: i64) -> Result<Vec<Bytes>> {
+        self.query(&[b"ZRANGE", key.as_bytes(), start.to_string().as_bytes(), stop.to_string().as_bytes()]).await
+    }
+
+    /// Like `zrange`, with each member's score.
+    pub async fn zrange_withscores(&mut self, key: &str, start: i64, stop: i64) -> Result<Vec<(Bytes, f64)>> {
+        let (start, stop) = (start.to_string(), stop.to_string());
+        self.query(&[b"ZRANGE", key.as_bytes(), start.as_bytes(), stop.as_bytes(), b"WITHSCORES"]).await
+    }
+
+    /// Walks the members of a sorted set with `ZSCAN`, yielding each member with its score.
+    pub fn zscan(&mut self, key: &str, opts: ScanOptions) -> ScanStream<'_, (Bytes, f64)> {
+        let command = vec![Bytes::from_static(b"ZSCAN"), Bytes::copy_from_slice(key.as_bytes())];
+        self.scanner(command, opts, Vec::from_frame)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::scan::test::collect;
+    use crate::test::{converse, mock};
+
+    #[tokio::test]
+    pub async fn test_zadd() {
+        let mut conn =
+            mock(b"*6\r\n$4\r\nZADD\r\n$5\r\nboard\r\n$3\r\n1.5\r\n$5\r\nalice\r\n$1\r\n2\r\n$3\r\nbob\r\n", b":2\r\n")
+                .await;
+
+        assert_eq!(2, conn.zadd("board", &[(1.5, "alice"), (2.0, "bob")]).await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_zscore() {
+        let mut conn = mock(b"*3\r\n$6\r\nZSCORE\r\n$5\r\nboard\r\n$5\r\nalice\r\n", b"$3\r\n1.5\r\n").await;
+        assert_eq!(Some(1.5), conn.zscore("board", "alice").await.unwrap());
+
+        let mut conn = mock(b"*3\r\n$6\r\nZSCORE\r\n$5\r\nboard\r\n$5\r\nalice\r\n", b",1.5\r\n").await;
+        assert_eq!(Some(1.5), conn.zscore("board", "alice").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_zscore_missing() {
+        let mut conn = mock(b"*3\r\n$6\r\nZSCORE\r\n$5\r\nboard\r\n$3\r\neve\r\n", b"$-1\r\n").await;
+
+        assert_eq!(None, conn.zscore("board", "eve").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_zrank() {
+        let mut conn = mock(b"*3\r\n$5\r\nZRANK\r\n$5\r\nboard\r\n$3\r\nbob\r\n", b":1\r\n").await;
+        assert_eq!(Some(1), conn.zrank("board", "bob").await.unwrap());
+
+        let mut conn = mock(b"*3\r\n$5\r\nZRANK\r\n$5\r\nboard\r\n$3\r\neve\r\n", b"$-1\r\n").await;
+        assert_eq!(None, conn.zrank("board", "eve").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn test_zrange() {
+        let mut conn = mock(
+            b"*4\r\n$6\r\nZRANGE\r\n$5\r\nboard\r\n$1\r\n0\r\n$2\r\n-1\r\n",
+            b"*2\r\n$5\r\nalice\r\n$3\r\nbob\r\n",
+        )
+        .await;
+
+        let members = conn.zrange("board", 0, -1).await.unwrap();
+
+        assert_eq!(vec![Bytes::from_static(b"alice"), Bytes::from_static(b"bob")], members);
+    }
+
+    #[tokio::test]
+    pub async fn test_zrange_withscores_resp2() {
+        let mut conn = mock(
+            b"*5\r\n$6\r\nZRANGE\r\n$5\r\nboard\r\n$1\r\n0\r\n$2\r\n-1\r\n$10\r\nWITHSCORES\r\n",
+            b"*4\r\n$5\r\nalice\r\n$3\r\n1.5\r\n$3\r\nbob\r\n$1\r\n2\r\n",
+        )
+        .await;
+
+        let members = conn.zrange_withscores("board", 0, -1).await.unwrap();
+
+        assert_eq!(vec![(Bytes::from_static(b"alice"), 1.5), (Bytes::from_static(b"bob"), 2.0)], members);
+    }
+
+    #[tokio::test]
+    pub async fn test_zrange_withscores_resp3() {
+        let mut conn = mock(
+            b"*5\r\n$6\r\nZRANGE\r\n$5\r\nboard\r\n$1\r\n0\r\n$2\r\n-1\r\n$10\r\nWITHSCORES\r\n",
+            b"*2\r\n*2\r\n$5\r\nalice\r\n,1.5\r\n*2\r\n$3\r\nbob\r\n,2\r\n",
+        )
+        .await;
+
+        let members = conn.zrange_withscores("board", 0, -1).await.unwrap();
+
+        assert_eq!(vec![(Bytes::from_static(b"alice"), 1.5), (Bytes::from_static(b"bob"), 2.0)], members);
+    }
+
+    #[tokio::test]
+    pub async fn test_zscan() {
+        let addr = converse(vec![(
+            b"*5\r\n$5\r\nZSCAN\r\n$5\r\nboard\r\n$1\r\n0\r\n$5\r\nCOUNT\r\n$2\r\n10\r\n",
+            b"*2\r\n$1\r\n0\r\n*4\r\n$5\r\nalice\r\n$3\r\n1.5\r\n$3\r\nbob\r\n$2\r\n-2\r\n",
+        )])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let members = collect(conn.zscan("board", ScanOptions::new().count(10))).await;
+
+        assert_eq!(vec![(Bytes::from_static(b"alice"), 1.5), (Bytes::from_static(b"bob"), -2.0)], members);
+    }
+
+    #[tokio::test]
+    pub async fn test_zscan_pages() {
+        let addr = converse(vec![
+            (
+                b"*3\r\n$5\r\nZSCAN\r\n$5\r\nboard\r\n$1\r\n0\r\n",
+                b"*2\r\n$1\r\n2\r\n*2\r\n$5\r\nalice\r\n$3\r\n1.5\r\n",
+            ),
+            (b"*3\r\n$5\r\nZSCAN\r\n$5\r\nboard\r\n$1\r\n2\r\n", b"*2\r\n$1\r\n0\r\n*2\r\n$3\r\nbob\r\n$3\r\ninf\r\n"),
+        ])
+        .await;
+        let mut conn = Connection::connect(addr).await.unwrap();
+
+        let members = collect(conn.zscan("board", ScanOptions::new())).await;
+
+        assert_eq!(vec![(Bytes::from_static(b"alice"), 1.5), (Bytes::from_static(b"bob"), f64::INFINITY)], members);
+    }
+}